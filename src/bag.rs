@@ -1,20 +1,22 @@
-//! 7-bag randomizer for piece generation
+//! Piece queue fed by a randomizer
 //!
-//! Tetris uses a "7-bag" system where all 7 pieces are shuffled,
-//! then dealt out before reshuffling. This prevents long droughts.
+//! The default randomizer is the "7-bag" system where all 7 pieces are
+//! shuffled, then dealt out before reshuffling. This prevents long droughts.
+//! Other randomizers live in the `randomizer` module.
 
+use crate::randomizer::{Randomizer, RandomizerKind};
 use crate::tetromino::TetrominoType;
-use rand::seq::SliceRandom;
-use rand::SeedableRng;
-use rand_chacha::ChaCha8Rng;
 
-/// The 7-bag piece randomizer
+/// Number of upcoming pieces kept ready for the preview
+const QUEUE_SIZE: usize = 14;
+
+/// Upcoming pieces dealt by a randomizer
 #[derive(Debug, Clone)]
 pub struct Bag {
     /// Preview queue for upcoming pieces
     queue: Vec<TetrominoType>,
-    /// Seeded RNG for deterministic piece generation
-    rng: ChaCha8Rng,
+    /// Seeded randomizer for deterministic piece generation
+    randomizer: Box<dyn Randomizer>,
 }
 
 impl Default for Bag {
@@ -24,30 +26,33 @@ impl Default for Bag {
 }
 
 impl Bag {
-    /// Create a new bag randomizer with random seed
+    /// Create a new 7-bag randomizer with random seed
     pub fn new() -> Self {
         Self::with_seed(rand::random())
     }
 
-    /// Create a new bag with specific seed (for multiplayer sync)
+    /// Create a new 7-bag with specific seed (for multiplayer sync)
     pub fn with_seed(seed: u64) -> Self {
+        Self::with_randomizer(RandomizerKind::SevenBag, seed)
+    }
+
+    /// Create a queue dealt by the given randomizer and seed
+    pub fn with_randomizer(kind: RandomizerKind, seed: u64) -> Self {
         let mut bag = Self {
-            queue: Vec::with_capacity(14),
-            rng: ChaCha8Rng::seed_from_u64(seed),
+            queue: Vec::with_capacity(QUEUE_SIZE),
+            randomizer: kind.create(seed),
         };
-        // Fill the queue with at least 2 full bags
-        bag.refill();
         bag.refill();
         bag
     }
 
     /// Get the next piece from the queue
+    // Never runs out, so this isn't an `Iterator`
+    #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> TetrominoType {
-        // Ensure we always have pieces in the queue
-        if self.queue.len() <= 7 {
-            self.refill();
-        }
-        self.queue.remove(0)
+        let piece = self.queue.remove(0);
+        self.refill();
+        piece
     }
 
     /// Preview the next N pieces without removing them
@@ -55,11 +60,16 @@ impl Bag {
         &self.queue[..count.min(self.queue.len())]
     }
 
-    /// Refill the queue with a new shuffled bag
+    /// Replace the next piece (practice), the rest of the queue is unchanged
+    pub fn choose_next(&mut self, piece: TetrominoType) {
+        self.queue[0] = piece;
+    }
+
+    /// Top the queue back up from the randomizer
     fn refill(&mut self) {
-        let mut new_bag = TetrominoType::all().to_vec();
-        new_bag.shuffle(&mut self.rng);
-        self.queue.extend(new_bag);
+        while self.queue.len() < QUEUE_SIZE {
+            self.queue.push(self.randomizer.next_piece());
+        }
     }
 }
 
@@ -90,6 +100,19 @@ mod tests {
         assert_eq!(preview.len(), 5);
     }
 
+    #[test]
+    fn test_choose_next_and_clone() {
+        let mut bag = Bag::with_seed(4);
+        bag.choose_next(TetrominoType::T);
+        let mut copy = bag.clone();
+        assert_eq!(bag.next(), TetrominoType::T);
+        assert_eq!(copy.next(), TetrominoType::T);
+        // The copy deals on from the same randomizer state
+        for _ in 0..20 {
+            assert_eq!(bag.next(), copy.next());
+        }
+    }
+
     #[test]
     fn test_many_pieces() {
         let mut bag = Bag::new();
@@ -1,7 +1,6 @@
 //! Game board representation and collision detection
 
 use crate::tetromino::TetrominoType;
-use ratatui::style::Color;
 
 /// Standard Tetris board dimensions
 pub const BOARD_WIDTH: usize = 10;
@@ -9,13 +8,21 @@ pub const BOARD_HEIGHT: usize = 20;
 /// Hidden rows above the visible board for spawning
 pub const BUFFER_HEIGHT: usize = 4;
 pub const TOTAL_HEIGHT: usize = BOARD_HEIGHT + BUFFER_HEIGHT;
-
-/// A cell on the board - either empty or filled with a color
+/// Smallest board a piece can spawn and rotate on
+pub const MIN_WIDTH: usize = 4;
+pub const MIN_HEIGHT: usize = 4;
+/// A cell on the board - empty or occupied by a mino
+/// Occupied cells remember where the mino came from; colors are up to the UI
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Cell {
     #[default]
     Empty,
-    Filled(Color),
+    /// Mino locked from a piece of this type
+    Filled(TetrominoType),
+    /// Garbage row mino sent by an opponent
+    Garbage,
+    /// Mino locked by an effect rather than a normal piece (e.g. bone blocks)
+    Bone,
 }
 
 impl Cell {
@@ -24,7 +31,15 @@ impl Cell {
     }
 
     pub fn is_filled(&self) -> bool {
-        matches!(self, Cell::Filled(_))
+        !self.is_empty()
+    }
+
+    /// Piece type this mino was locked from, if any
+    pub fn piece_type(&self) -> Option<TetrominoType> {
+        match self {
+            Cell::Filled(piece_type) => Some(*piece_type),
+            _ => None,
+        }
     }
 }
 
@@ -32,7 +47,11 @@ impl Cell {
 #[derive(Debug, Clone)]
 pub struct Board {
     /// Grid stored as [row][col], row 0 is bottom, row increases upward
-    cells: [[Cell; BOARD_WIDTH]; TOTAL_HEIGHT],
+    /// Includes the buffer rows above the visible board
+    cells: Vec<Vec<Cell>>,
+    width: usize,
+    /// Visible height, not counting the buffer
+    height: usize,
 }
 
 impl Default for Board {
@@ -42,13 +61,38 @@ impl Default for Board {
 }
 
 impl Board {
-    /// Create a new empty board
+    /// Create a new empty board of the standard size
     pub fn new() -> Self {
+        Self::with_size(BOARD_WIDTH, BOARD_HEIGHT)
+    }
+
+    /// Create a new empty board with a visible area of `width` x `height`
+    /// Sizes below the minimum are clamped up to it
+    pub fn with_size(width: usize, height: usize) -> Self {
+        let width = width.max(MIN_WIDTH);
+        let height = height.max(MIN_HEIGHT);
         Self {
-            cells: [[Cell::Empty; BOARD_WIDTH]; TOTAL_HEIGHT],
+            cells: vec![vec![Cell::Empty; width]; height + BUFFER_HEIGHT],
+            width,
+            height,
         }
     }
 
+    /// Number of columns
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Number of visible rows
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Number of rows including the buffer
+    pub fn total_height(&self) -> usize {
+        self.cells.len()
+    }
+
     /// Get the cell at a position (row, col)
     /// Returns None if out of bounds
     pub fn get(&self, row: i32, col: i32) -> Option<Cell> {
@@ -57,7 +101,7 @@ impl Board {
         }
         let row = row as usize;
         let col = col as usize;
-        if row >= TOTAL_HEIGHT || col >= BOARD_WIDTH {
+        if row >= self.total_height() || col >= self.width {
             return None;
         }
         Some(self.cells[row][col])
@@ -71,7 +115,7 @@ impl Board {
         }
         let row = row as usize;
         let col = col as usize;
-        if row >= TOTAL_HEIGHT || col >= BOARD_WIDTH {
+        if row >= self.total_height() || col >= self.width {
             return false;
         }
         self.cells[row][col] = cell;
@@ -80,13 +124,13 @@ impl Board {
 
     /// Check if a position is valid (within bounds and empty)
     pub fn is_valid_position(&self, row: i32, col: i32) -> bool {
-        if col < 0 || col >= BOARD_WIDTH as i32 {
+        if col < 0 || col >= self.width as i32 {
             return false;
         }
         if row < 0 {
             return false;
         }
-        if row >= TOTAL_HEIGHT as i32 {
+        if row >= self.total_height() as i32 {
             // Above the board is valid (for spawning)
             return true;
         }
@@ -102,9 +146,8 @@ impl Board {
 
     /// Lock a piece onto the board
     pub fn lock_piece(&mut self, positions: &[(i32, i32)], piece_type: TetrominoType) {
-        let color = piece_type.color();
         for &(row, col) in positions {
-            self.set(row, col, Cell::Filled(color));
+            self.set(row, col, Cell::Filled(piece_type));
         }
     }
 
@@ -113,11 +156,11 @@ impl Board {
         let mut lines_cleared = 0;
         let mut write_row = 0;
 
-        for read_row in 0..TOTAL_HEIGHT {
+        for read_row in 0..self.total_height() {
             if !self.is_line_full(read_row) {
                 // Keep this line
                 if write_row != read_row {
-                    self.cells[write_row] = self.cells[read_row];
+                    self.cells.swap(write_row, read_row);
                 }
                 write_row += 1;
             } else {
@@ -126,13 +169,50 @@ impl Board {
         }
 
         // Fill the top with empty rows
-        for row in write_row..TOTAL_HEIGHT {
-            self.cells[row] = [Cell::Empty; BOARD_WIDTH];
+        for row in &mut self.cells[write_row..] {
+            row.fill(Cell::Empty);
         }
 
         lines_cleared
     }
 
+    /// Push garbage rows up from the bottom, each with a hole at `hole_col`
+    /// Returns false if filled cells were pushed off the top of the board
+    pub fn add_garbage(&mut self, lines: usize, hole_col: usize) -> bool {
+        let total_height = self.total_height();
+        let lines = lines.min(total_height);
+        if lines == 0 {
+            return true;
+        }
+
+        // Anything in the top rows falls off the board when shifted
+        let overflow = self.cells[total_height - lines..]
+            .iter()
+            .any(|row| row.iter().any(|cell| cell.is_filled()));
+
+        // Shift everything up by `lines` rows
+        self.cells.rotate_right(lines);
+
+        // Fill the bottom with garbage rows
+        let mut garbage_row = vec![Cell::Garbage; self.width];
+        if hole_col < self.width {
+            garbage_row[hole_col] = Cell::Empty;
+        }
+        for row in &mut self.cells[..lines] {
+            row.clone_from(&garbage_row);
+        }
+
+        !overflow
+    }
+
+    /// Number of rows with any garbage in them
+    pub fn garbage_rows(&self) -> usize {
+        self.cells
+            .iter()
+            .filter(|row| row.contains(&Cell::Garbage))
+            .count()
+    }
+
     /// Check if a line is completely filled
     fn is_line_full(&self, row: usize) -> bool {
         self.cells[row].iter().all(|cell| cell.is_filled())
@@ -147,21 +227,17 @@ impl Board {
 
     /// Get an iterator over visible rows (bottom to top)
     #[allow(dead_code)]
-    pub fn visible_rows(&self) -> impl Iterator<Item = (usize, &[Cell; BOARD_WIDTH])> {
-        self.cells[..BOARD_HEIGHT].iter().enumerate()
+    pub fn visible_rows(&self) -> impl Iterator<Item = (usize, &[Cell])> {
+        self.cells[..self.height].iter().map(Vec::as_slice).enumerate()
     }
 
     /// Check if game is over (blocks in the buffer zone that are locked)
-    #[allow(dead_code)]
     pub fn is_topped_out(&self) -> bool {
         // Check if any cells in the visible top rows are filled
-        // Game over when pieces stack above row 20
-        for row in BOARD_HEIGHT..TOTAL_HEIGHT {
-            if self.cells[row].iter().any(|cell| cell.is_filled()) {
-                return true;
-            }
-        }
-        false
+        // Game over when pieces stack above the visible board
+        self.cells[self.height..]
+            .iter()
+            .any(|row| row.iter().any(|cell| cell.is_filled()))
     }
 }
 
@@ -178,8 +254,8 @@ mod tests {
     #[test]
     fn test_set_and_get() {
         let mut board = Board::new();
-        assert!(board.set(5, 5, Cell::Filled(Color::Red)));
-        assert_eq!(board.get(5, 5), Some(Cell::Filled(Color::Red)));
+        assert!(board.set(5, 5, Cell::Filled(TetrominoType::Z)));
+        assert_eq!(board.get(5, 5), Some(Cell::Filled(TetrominoType::Z)));
     }
 
     #[test]
@@ -196,15 +272,67 @@ mod tests {
         let mut board = Board::new();
         // Fill the bottom row
         for col in 0..BOARD_WIDTH {
-            board.set(0, col as i32, Cell::Filled(Color::Cyan));
+            board.set(0, col as i32, Cell::Filled(TetrominoType::I));
         }
         // Add a block on row 1
-        board.set(1, 0, Cell::Filled(Color::Red));
+        board.set(1, 0, Cell::Filled(TetrominoType::Z));
 
         let cleared = board.clear_lines();
         assert_eq!(cleared, 1);
         // The block from row 1 should now be on row 0
-        assert_eq!(board.get(0, 0), Some(Cell::Filled(Color::Red)));
+        assert_eq!(board.get(0, 0), Some(Cell::Filled(TetrominoType::Z)));
         assert!(board.get(1, 0).unwrap().is_empty());
     }
+
+    #[test]
+    fn test_add_garbage() {
+        let mut board = Board::new();
+        board.set(0, 3, Cell::Filled(TetrominoType::Z));
+
+        assert!(board.add_garbage(2, 7));
+        // Existing block pushed up above the garbage
+        assert_eq!(board.get(2, 3), Some(Cell::Filled(TetrominoType::Z)));
+        // Garbage rows have a single hole in the same column
+        for row in 0..2 {
+            for col in 0..BOARD_WIDTH as i32 {
+                assert_eq!(board.get(row, col).unwrap().is_empty(), col == 7);
+            }
+        }
+        assert!(!board.is_topped_out());
+        assert_eq!(board.garbage_rows(), 2);
+    }
+
+    #[test]
+    fn test_garbage_top_out() {
+        let mut board = Board::new();
+        board.set(BOARD_HEIGHT as i32 - 1, 0, Cell::Filled(TetrominoType::Z));
+        assert!(board.add_garbage(1, 0));
+        // Stack pushed into the buffer zone
+        assert!(board.is_topped_out());
+
+        let mut board = Board::new();
+        board.set(TOTAL_HEIGHT as i32 - 1, 0, Cell::Filled(TetrominoType::Z));
+        // Block pushed off the top entirely
+        assert!(!board.add_garbage(1, 0));
+    }
+
+    #[test]
+    fn test_custom_size() {
+        let mut board = Board::with_size(4, 40);
+        assert_eq!(board.total_height(), 40 + BUFFER_HEIGHT);
+        assert!(board.is_valid_position(0, 3));
+        assert!(!board.is_valid_position(0, 4));
+
+        for col in 0..4 {
+            board.set(0, col, Cell::Filled(TetrominoType::I));
+        }
+        assert_eq!(board.clear_lines(), 1);
+        assert!(board.is_empty());
+
+        // Stacking past row 40 tops out, not past row 20
+        board.set(30, 0, Cell::Garbage);
+        assert!(!board.is_topped_out());
+        board.set(40, 0, Cell::Garbage);
+        assert!(board.is_topped_out());
+    }
 }
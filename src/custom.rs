@@ -0,0 +1,302 @@
+//! Custom modes: a goal and rules picked by the player
+//!
+//! Custom modes are saved to `custom_modes.toml` in the config dir, one
+//! `[[modes]]` table each, and can be edited there by hand. They're played as
+//! `GameMode::Custom`, with the definition carried in the game's `ModeState`.
+
+use crate::gravity::GravityCurve;
+use crate::randomizer::RandomizerKind;
+use crate::rules::Rules;
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Starting levels a custom mode may use
+pub const STARTING_LEVELS: RangeInclusive<u32> = 1..=20;
+/// Most next pieces a custom mode may show
+pub const MAX_PREVIEW: usize = 5;
+/// Board widths a custom mode may use
+pub const BOARD_WIDTHS: RangeInclusive<usize> = 4..=20;
+/// Board heights a custom mode may use
+pub const BOARD_HEIGHTS: RangeInclusive<usize> = 4..=40;
+
+/// What ends a custom game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "target", rename_all = "lowercase")]
+pub enum Goal {
+    /// Play until topping out
+    Endless,
+    /// Clear this many lines
+    Lines(u32),
+    /// Score as much as possible in this many seconds
+    Time(u64),
+    /// Reach this many points
+    Score(u64),
+    /// Place this many pieces
+    Pieces(u32),
+}
+
+impl Default for Goal {
+    fn default() -> Self {
+        Goal::Lines(40)
+    }
+}
+
+impl Goal {
+    /// Goals offered in the custom mode builder
+    pub fn presets() -> Vec<Goal> {
+        vec![
+            Goal::Lines(20),
+            Goal::Lines(40),
+            Goal::Lines(150),
+            Goal::Time(60),
+            Goal::Time(120),
+            Goal::Time(180),
+            Goal::Score(10_000),
+            Goal::Score(100_000),
+            Goal::Pieces(100),
+            Goal::Pieces(500),
+            Goal::Endless,
+        ]
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Goal::Endless => "Endless".to_string(),
+            Goal::Lines(lines) => format!("{} Lines", lines),
+            Goal::Time(secs) => format!("{}:{:02}", secs / 60, secs % 60),
+            Goal::Score(points) => format!("{} Points", points),
+            Goal::Pieces(pieces) => format!("{} Pieces", pieces),
+        }
+    }
+
+    /// Whether the goal is reached
+    pub fn is_met(&self, elapsed: Duration, lines: u32, points: u64, pieces: u32) -> bool {
+        match *self {
+            Goal::Endless => false,
+            Goal::Lines(target) => lines >= target,
+            Goal::Time(secs) => elapsed >= Duration::from_secs(secs),
+            Goal::Score(target) => points >= target,
+            Goal::Pieces(target) => pieces >= target,
+        }
+    }
+
+    /// Whether reaching the goal is a race (ranked by time) rather than
+    /// a score attack
+    pub fn ranked_by_time(&self) -> bool {
+        matches!(self, Goal::Lines(_) | Goal::Score(_) | Goal::Pieces(_))
+    }
+}
+
+/// A player-defined mode
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CustomMode {
+    /// Shown in the menu, and names the mode's leaderboard
+    pub name: String,
+    pub goal: Goal,
+    pub starting_level: u32,
+    pub gravity: GravityCurve,
+    pub randomizer: RandomizerKind,
+    /// Number of next pieces shown
+    pub preview_count: usize,
+    pub hold: bool,
+    pub ghost: bool,
+    pub board_width: usize,
+    pub board_height: usize,
+}
+
+impl Default for CustomMode {
+    fn default() -> Self {
+        let rules = Rules::default();
+        Self {
+            name: "Custom".to_string(),
+            goal: Goal::default(),
+            starting_level: 1,
+            gravity: rules.gravity,
+            randomizer: rules.randomizer,
+            preview_count: rules.preview_count,
+            hold: rules.hold,
+            ghost: rules.ghost,
+            board_width: rules.board_width,
+            board_height: rules.board_height,
+        }
+    }
+}
+
+/// Layout of `custom_modes.toml`
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct CustomModesFile {
+    modes: Vec<CustomMode>,
+}
+
+impl CustomMode {
+    /// Rules for a game of this mode, before any settings overrides
+    pub fn rules(&self) -> Rules {
+        Rules {
+            gravity: self.gravity.clone(),
+            randomizer: self.randomizer,
+            preview_count: self.preview_count,
+            hold: self.hold,
+            ghost: self.ghost,
+            board_width: self.board_width,
+            board_height: self.board_height,
+            ..Rules::default()
+        }
+    }
+
+    /// Get the custom modes file path
+    fn modes_path() -> Option<PathBuf> {
+        Settings::config_dir().map(|dir| dir.join("custom_modes.toml"))
+    }
+
+    /// Keep hand-edited values within what the builder offers
+    fn clamp(&mut self) {
+        self.starting_level = self.starting_level.clamp(*STARTING_LEVELS.start(), *STARTING_LEVELS.end());
+        self.preview_count = self.preview_count.min(MAX_PREVIEW);
+        self.board_width = self.board_width.clamp(*BOARD_WIDTHS.start(), *BOARD_WIDTHS.end());
+        self.board_height = self.board_height.clamp(*BOARD_HEIGHTS.start(), *BOARD_HEIGHTS.end());
+    }
+
+    /// Parse the contents of `custom_modes.toml`, clamping every mode
+    fn parse_all(contents: &str) -> Result<Vec<CustomMode>, String> {
+        let file: CustomModesFile =
+            toml::from_str(contents).map_err(|e| format!("Invalid custom_modes.toml: {}", e))?;
+        let mut modes = file.modes;
+        modes.iter_mut().for_each(CustomMode::clamp);
+        Ok(modes)
+    }
+
+    /// Load all saved custom modes (none if the file is missing)
+    pub fn load_all() -> Result<Vec<CustomMode>, String> {
+        let Some(path) = Self::modes_path() else {
+            return Ok(Vec::new());
+        };
+        match fs::read_to_string(path) {
+            Ok(contents) => Self::parse_all(&contents),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(format!("Failed to read custom modes: {}", e)),
+        }
+    }
+
+    /// Find a saved custom mode by name
+    pub fn find(name: &str) -> Option<CustomMode> {
+        Self::load_all().ok()?.into_iter().find(|mode| mode.name == name)
+    }
+
+    /// Save this mode as a new one. The name must be set and not taken,
+    /// since it names the mode's leaderboard. Nothing is written if the
+    /// saved modes can't be read, so they aren't lost.
+    pub fn save(&self) -> Result<(), String> {
+        let Some(dir) = Settings::config_dir() else {
+            return Err("Could not determine config directory".to_string());
+        };
+        let Some(path) = Self::modes_path() else {
+            return Err("Could not determine custom modes path".to_string());
+        };
+
+        let mut modes = Self::load_all()?;
+        Self::check_name(&self.name, &modes)?;
+        modes.push(self.clone());
+
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
+        let contents = toml::to_string_pretty(&CustomModesFile { modes })
+            .map_err(|e| format!("Failed to serialize: {}", e))?;
+        fs::write(&path, contents).map_err(|e| format!("Failed to write custom modes: {}", e))?;
+
+        Ok(())
+    }
+
+    /// A new mode's name must be set and not used by a saved mode
+    fn check_name(name: &str, saved: &[CustomMode]) -> Result<(), String> {
+        if name.trim().is_empty() {
+            return Err("Give the mode a name".to_string());
+        }
+        if saved.iter().any(|mode| mode.name == name) {
+            return Err(format!("A mode named \"{}\" already exists", name));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toml_round_trip() {
+        let file = CustomModesFile {
+            modes: vec![
+                CustomMode {
+                    name: "Big board".to_string(),
+                    goal: Goal::Time(90),
+                    gravity: GravityCurve::Fixed(0.5),
+                    board_width: 16,
+                    ..CustomMode::default()
+                },
+                CustomMode::default(),
+            ],
+        };
+        let contents = toml::to_string_pretty(&file).unwrap();
+        let loaded: CustomModesFile = toml::from_str(&contents).unwrap();
+        assert_eq!(loaded.modes, file.modes);
+    }
+
+    #[test]
+    fn test_hand_written_mode() {
+        // Anything left out takes the default
+        let contents = r#"
+            [[modes]]
+            name = "No hold"
+            goal = { kind = "pieces", target = 200 }
+            hold = false
+        "#;
+        let file: CustomModesFile = toml::from_str(contents).unwrap();
+        let mode = &file.modes[0];
+        assert_eq!(mode.goal, Goal::Pieces(200));
+        assert!(!mode.rules().hold);
+        assert_eq!(mode.board_width, 10);
+    }
+
+    #[test]
+    fn test_hand_edits_clamped() {
+        let contents = r#"
+            [[modes]]
+            name = "Huge"
+            preview_count = 14
+            board_width = 1000
+            board_height = 1
+        "#;
+        let mode = &CustomMode::parse_all(contents).unwrap()[0];
+        assert_eq!(mode.preview_count, MAX_PREVIEW);
+        assert_eq!(mode.board_width, 20);
+        assert_eq!(mode.board_height, 4);
+
+        assert!(CustomMode::parse_all("[[modes]]\nname = ").is_err());
+    }
+
+    #[test]
+    fn test_names_checked() {
+        let saved = vec![CustomMode {
+            name: "Mine".to_string(),
+            ..CustomMode::default()
+        }];
+        assert!(CustomMode::check_name("Other", &saved).is_ok());
+        assert!(CustomMode::check_name("Mine", &saved).is_err());
+        assert!(CustomMode::check_name("  ", &saved).is_err());
+    }
+
+    #[test]
+    fn test_goals() {
+        assert!(Goal::Lines(40).is_met(Duration::ZERO, 40, 0, 0));
+        assert!(!Goal::Time(60).is_met(Duration::from_secs(59), 100, 0, 0));
+        assert!(Goal::Score(1000).is_met(Duration::ZERO, 0, 1200, 0));
+        assert!(!Goal::Endless.is_met(Duration::MAX, u32::MAX, u64::MAX, u32::MAX));
+        assert!(!Goal::Time(60).ranked_by_time());
+    }
+}
@@ -0,0 +1,133 @@
+//! Daily challenge: a 40 line race on the same pieces for everyone
+//!
+//! The seed comes from the UTC date, so every copy of the game deals the
+//! same sequence on the same day. Attempts are kept per day in the settings
+//! file, and a day's result can be exported as one line to share.
+
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Lines to clear in the daily race
+pub const DAILY_LINES: u32 = 40;
+
+/// Days since the Unix epoch, in UTC
+pub fn today() -> u64 {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    secs / 86400
+}
+
+/// A day as YYYY-MM-DD
+pub fn date_string(day: u64) -> String {
+    // Civil date from days, shifted so years start in March
+    let days = day as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day_of_month = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{:04}-{:02}-{:02}", year, month, day_of_month)
+}
+
+/// Seed for a day's pieces (SplitMix64 of the day, stable across builds)
+pub fn seed(day: u64) -> u64 {
+    let mut z = day.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// A day's attempts at the challenge
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DailyRecord {
+    pub attempts: u32,
+    /// Fastest finish in milliseconds (None until one is finished)
+    pub best_ms: Option<u64>,
+}
+
+impl DailyRecord {
+    /// Count an attempt, with its time if the race was finished
+    pub fn add_attempt(&mut self, finished_ms: Option<u64>) {
+        self.attempts += 1;
+        if let Some(time_ms) = finished_ms {
+            self.best_ms = Some(self.best_ms.map_or(time_ms, |best| best.min(time_ms)));
+        }
+    }
+
+    /// Best time and attempts, e.g. "01:02.345, 3 tries"
+    pub fn summary(&self) -> String {
+        let best = self.best_ms.map_or("not finished".to_string(), format_ms);
+        let tries = if self.attempts == 1 { "try" } else { "tries" };
+        format!("{}, {} {}", best, self.attempts, tries)
+    }
+
+    /// One line result to share, e.g.
+    /// "tetrs daily 2026-10-17: 40L in 01:02.345 (3 tries)"
+    pub fn share_line(&self, date: &str) -> String {
+        let result = self
+            .best_ms
+            .map_or("DNF".to_string(), |best| format!("{}L in {}", DAILY_LINES, format_ms(best)));
+        let tries = if self.attempts == 1 { "try" } else { "tries" };
+        format!("tetrs daily {}: {} ({} {})", date, result, self.attempts, tries)
+    }
+}
+
+/// Write a share line to `daily.txt` in the config dir, returns the file path
+pub fn export(line: &str) -> Result<PathBuf, String> {
+    let Some(dir) = Settings::config_dir() else {
+        return Err("Could not determine config directory".to_string());
+    };
+
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
+    let path = dir.join("daily.txt");
+    fs::write(&path, format!("{}\n", line)).map_err(|e| format!("Failed to write daily result: {}", e))?;
+
+    Ok(path)
+}
+
+/// Format milliseconds as MM:SS.mmm
+fn format_ms(time_ms: u64) -> String {
+    let secs = time_ms / 1000;
+    format!("{:02}:{:02}.{:03}", secs / 60, secs % 60, time_ms % 1000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_date_string() {
+        assert_eq!(date_string(0), "1970-01-01");
+        assert_eq!(date_string(11_016), "2000-02-29");
+        assert_eq!(date_string(20_743), "2026-10-17");
+    }
+
+    #[test]
+    fn test_seed_per_day() {
+        assert_eq!(seed(20_743), seed(20_743));
+        assert_ne!(seed(20_743), seed(20_744));
+    }
+
+    #[test]
+    fn test_record_and_share() {
+        let mut record = DailyRecord::default();
+        record.add_attempt(None);
+        assert_eq!(record.share_line("2026-10-17"), "tetrs daily 2026-10-17: DNF (1 try)");
+
+        record.add_attempt(Some(65_432));
+        record.add_attempt(Some(70_000));
+        assert_eq!(record.best_ms, Some(65_432));
+        assert_eq!(record.summary(), "01:05.432, 3 tries");
+        assert_eq!(record.share_line("2026-10-17"), "tetrs daily 2026-10-17: 40L in 01:05.432 (3 tries)");
+    }
+}
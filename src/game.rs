@@ -1,12 +1,22 @@
 //! Core game state and logic
 
 use crate::bag::Bag;
-use crate::board::{Board, Cell, BOARD_HEIGHT};
+use crate::board::Board;
+use crate::custom::{CustomMode, Goal};
+use crate::daily;
+use crate::garbage::{calculate_garbage, GarbageQueue};
+use crate::gravity::TWENTY_G;
 use crate::mode::{GameMode, ModeState};
 use crate::piece::Piece;
+use crate::practice::{PracticeState, Snapshot};
+use crate::replay::{ReplayEvent, ReplayInput};
+use crate::rules::{LockReset, Rules};
 use crate::score::{ClearType, Score};
 use crate::tetromino::{RotationDirection, TetrominoType};
-use std::time::{Duration, Instant};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 /// Info about the last line clear (for garbage calculation in multiplayer)
 #[derive(Debug, Clone)]
@@ -17,9 +27,23 @@ pub struct ClearInfo {
     pub back_to_back: bool,
 }
 
-/// Lock delay settings
-const LOCK_DELAY: Duration = Duration::from_millis(500);
-const MAX_LOCK_RESETS: u8 = 15;
+/// Simulation rate: the game advances in fixed ticks of 1/60 s
+pub const TICK_RATE: u64 = 60;
+pub const TICK: Duration = Duration::from_nanos(1_000_000_000 / TICK_RATE);
+
+/// Game clock time at the start of a tick number
+pub fn frames_to_duration(frames: u64) -> Duration {
+    Duration::from_nanos(frames * 1_000_000_000 / TICK_RATE)
+}
+
+/// Nearest tick number for a game clock time
+pub fn duration_to_frames(time: Duration) -> u64 {
+    ((time.as_nanos() * TICK_RATE as u128 + 500_000_000) / 1_000_000_000) as u64
+}
+
+/// Slack when counting whole rows of gravity, so that 1/60 G falls on
+/// exactly the 60th frame despite floating point rounding
+const GRAVITY_EPSILON: f64 = 1e-9;
 
 /// Game state
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -32,7 +56,7 @@ pub enum GameState {
 }
 
 /// Input actions the game can process
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Action {
     MoveLeft,
     MoveRight,
@@ -40,9 +64,27 @@ pub enum Action {
     HardDrop,
     RotateCW,
     RotateCCW,
+    Rotate180,
     Hold,
     Pause,
     Quit,
+    /// Practice: take back the last placement
+    Undo,
+    /// Practice: place the last undone piece again
+    Redo,
+    /// Practice: back to the first piece
+    Reset,
+    /// Practice: change the next piece to the following type
+    CycleNext,
+}
+
+/// Rotate and hold buttons being held down, read as a piece spawns for
+/// IRS and IHS
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeldInput {
+    /// Direction of the rotate button held, if any
+    pub rotation: Option<RotationDirection>,
+    pub hold: bool,
 }
 
 /// The main game struct
@@ -55,32 +97,62 @@ pub struct Game {
     pub hold_piece: Option<TetrominoType>,
     /// Whether hold has been used this piece
     hold_used: bool,
+    /// Seed the piece sequence and garbage holes were generated from
+    seed: u64,
+    /// Rules this game is played with
+    rules: Rules,
     /// Piece bag randomizer
     bag: Bag,
+    /// Seeded RNG for garbage hole placement
+    garbage_rng: ChaCha8Rng,
+    /// Incoming garbage waiting to enter the board
+    pub garbage_queue: GarbageQueue,
+    /// Garbage lines to send to the opponent (after cancelling incoming)
+    outgoing_garbage: u8,
+    /// Total garbage lines sent this game
+    pub garbage_sent: u32,
     /// Score tracking
     pub score: Score,
     /// Current game state
     pub state: GameState,
     /// Game mode state
     pub mode_state: ModeState,
-    /// Last gravity tick
-    last_fall: Instant,
+    /// Ticks simulated since the game was created
+    frame: u64,
+    /// Tick when play started (after the countdown)
+    start_frame: Option<u64>,
+    /// Time passed to `advance` but not yet simulated (less than one tick)
+    pending: Duration,
+    /// Rows of gravity built up but not yet fallen (less than one)
+    fall_progress: f64,
     /// Lock delay timer (Some when piece is touching ground)
-    lock_timer: Option<Instant>,
+    lock_timer: Option<Duration>,
     /// Number of lock resets used
-    lock_resets: u8,
+    lock_resets: u32,
     /// Lowest row reached (for lock reset tracking)
     lowest_row: i32,
+    /// When the next piece spawns, while waiting out the entry delay
+    spawn_timer: Option<Duration>,
+    /// Rotation input during the entry delay, applied on spawn (IRS)
+    initial_rotation: Option<RotationDirection>,
+    /// Hold input during the entry delay, applied on spawn (IHS)
+    initial_hold: bool,
+    /// Rotate and hold buttons held down, also applied on spawn
+    held: HeldInput,
     /// Last action text to display
     pub last_action: Option<String>,
     /// Soft drop distance this piece (for scoring)
     soft_drop_distance: u32,
-    /// Countdown timer
-    countdown_start: Option<Instant>,
+    /// Countdown timer (game clock)
+    countdown_start: Option<Duration>,
     /// Flag set when a piece is locked (for multiplayer sync)
     pub piece_just_locked: bool,
+    /// Number of pieces locked this game
+    pub pieces_placed: u32,
     /// Last line clear info for garbage calculation
     pub last_clear_info: Option<ClearInfo>,
+    /// Inputs recorded since play started (for replays)
+    recorded_events: Vec<ReplayEvent>,
 }
 
 impl Game {
@@ -91,30 +163,75 @@ impl Game {
 
     /// Create a new game with specified mode and seed (for multiplayer)
     pub fn with_seed(mode: GameMode, seed: u64) -> Self {
-        let mut bag = Bag::with_seed(seed);
+        Self::with_rules(mode, seed, mode.rules())
+    }
+
+    /// Create a new game with specified mode, seed and rules
+    pub fn with_rules(mode: GameMode, seed: u64, rules: Rules) -> Self {
+        let board = Board::with_size(rules.board_width, rules.board_height);
+        let mut bag = Bag::with_randomizer(rules.randomizer, seed);
         let first_piece = bag.next();
         let mut score = Score::new();
-        score.level = mode.starting_level();
+        score.set_starting_level(mode.starting_level());
+        score.lines_per_level = rules.lines_per_level;
+        // Separate stream so hole placement doesn't disturb the piece sequence
+        let mut garbage_rng = ChaCha8Rng::seed_from_u64(seed);
+        garbage_rng.set_stream(1);
 
-        Self {
-            board: Board::new(),
-            current_piece: Some(Piece::new(first_piece)),
+        let mut game = Self {
+            current_piece: Some(Piece::spawn(first_piece, rules.rotation_system, &board)),
+            board,
             hold_piece: None,
             hold_used: false,
+            seed,
+            rules,
             bag,
+            garbage_rng,
+            garbage_queue: GarbageQueue::default(),
+            outgoing_garbage: 0,
+            garbage_sent: 0,
             score,
             state: GameState::Countdown(3),
             mode_state: ModeState::new(mode),
-            last_fall: Instant::now(),
+            frame: 0,
+            start_frame: None,
+            pending: Duration::ZERO,
+            fall_progress: 0.0,
             lock_timer: None,
             lock_resets: 0,
             lowest_row: i32::MAX,
+            spawn_timer: None,
+            initial_rotation: None,
+            initial_hold: false,
+            held: HeldInput::default(),
             last_action: None,
             soft_drop_distance: 0,
-            countdown_start: Some(Instant::now()),
+            countdown_start: Some(Duration::ZERO),
             piece_just_locked: false,
+            pieces_placed: 0,
             last_clear_info: None,
-        }
+            recorded_events: Vec::new(),
+        };
+        game.refill_cheese();
+        game.snapshot_spawn();
+        game
+    }
+
+    /// Create a game of a custom mode with the given seed and rules
+    /// (usually `custom.rules()` with settings overrides applied)
+    pub fn with_custom(custom: &CustomMode, seed: u64, rules: Rules) -> Self {
+        let mut game = Self::with_rules(GameMode::Custom, seed, rules);
+        game.score.set_starting_level(custom.starting_level);
+        game.mode_state = ModeState::for_custom(custom.clone());
+        game
+    }
+
+    /// Create a Daily challenge game for `day` (days since the epoch), dealt
+    /// that day's pieces with the mode's own rules
+    pub fn with_daily(day: u64) -> Self {
+        let mut game = Self::with_rules(GameMode::Daily, daily::seed(day), GameMode::Daily.rules());
+        game.mode_state.day = Some(day);
+        game
     }
 
     /// Get the current game mode
@@ -122,13 +239,54 @@ impl Game {
         self.mode_state.mode
     }
 
+    /// Name of the mode being played, custom modes by their own name
+    pub fn mode_name(&self) -> &str {
+        match &self.mode_state.custom {
+            Some(custom) => &custom.name,
+            None => self.mode().name(),
+        }
+    }
+
+    /// Get the seed this game was created with
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Get the rules this game is played with
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    /// Inputs recorded so far, stamped with the tick since play started
+    pub fn recorded_events(&self) -> &[ReplayEvent] {
+        &self.recorded_events
+    }
+
+    /// Record an input for the replay (only once play has started)
+    fn record(&mut self, input: ReplayInput) {
+        if let Some(frame) = self.play_frames() {
+            self.recorded_events.push(ReplayEvent { frame, input });
+        }
+    }
+
     /// Get preview of next pieces
     pub fn preview(&self) -> &[TetrominoType] {
-        self.bag.preview(5)
+        self.bag.preview(self.rules.preview_count)
+    }
+
+    /// Update the rotate and hold buttons held down. Held buttons only
+    /// count once play has started.
+    pub fn set_held(&mut self, held: HeldInput) {
+        if held == self.held || self.play_frames().is_none() {
+            return;
+        }
+        self.record(ReplayInput::Held(held));
+        self.held = held;
     }
 
     /// Process an action
     pub fn process_action(&mut self, action: Action) {
+        self.record(ReplayInput::Action(action));
         match self.state {
             GameState::Countdown(_) => {
                 // No actions during countdown
@@ -136,7 +294,6 @@ impl Game {
             GameState::Paused => {
                 if action == Action::Pause {
                     self.state = GameState::Playing;
-                    self.last_fall = Instant::now();
                 }
             }
             GameState::Playing => match action {
@@ -146,6 +303,7 @@ impl Game {
                 Action::HardDrop => self.hard_drop(),
                 Action::RotateCW => self.rotate(RotationDirection::Clockwise),
                 Action::RotateCCW => self.rotate(RotationDirection::CounterClockwise),
+                Action::Rotate180 => self.rotate(RotationDirection::Half),
                 Action::Hold => self.hold(),
                 Action::Pause => {
                     self.state = GameState::Paused;
@@ -153,6 +311,10 @@ impl Game {
                 Action::Quit => {
                     self.state = GameState::GameOver;
                 }
+                Action::Undo => self.practice_undo(),
+                Action::Redo => self.practice_redo(),
+                Action::Reset => self.practice_reset(),
+                Action::CycleNext => self.cycle_next(),
             },
             GameState::GameOver | GameState::Victory => {
                 // No actions, handled by main loop
@@ -160,18 +322,52 @@ impl Game {
         }
     }
 
-    /// Update game state (call every frame)
-    pub fn update(&mut self) {
+    /// Get the game clock (time simulated since the game was created)
+    pub fn clock(&self) -> Duration {
+        frames_to_duration(self.frame)
+    }
+
+    /// Ticks since play started (None during the countdown)
+    pub fn play_frames(&self) -> Option<u64> {
+        self.start_frame.map(|start| self.frame - start)
+    }
+
+    /// Skip the countdown and start play now (Versus has its own countdown)
+    pub fn skip_countdown(&mut self) {
+        if matches!(self.state, GameState::Countdown(_)) {
+            self.start_play();
+        }
+    }
+
+    fn start_play(&mut self) {
+        self.state = GameState::Playing;
+        self.start_frame = Some(self.frame);
+        self.mode_state.start(self.clock());
+        self.countdown_start = None;
+    }
+
+    /// Advance the game by `dt`, running as many whole ticks as fit
+    /// The remainder carries over to the next call
+    pub fn advance(&mut self, dt: Duration) {
+        self.pending += dt;
+        while self.pending >= TICK {
+            self.pending -= TICK;
+            self.tick();
+        }
+    }
+
+    /// Advance the game by exactly one tick
+    pub fn tick(&mut self) {
+        self.frame += 1;
+        let now = self.clock();
+
         // Handle countdown
         if let GameState::Countdown(count) = self.state {
             if let Some(start) = self.countdown_start {
-                let elapsed = start.elapsed().as_secs();
+                let elapsed = (now - start).as_secs();
                 let new_count = 3u8.saturating_sub(elapsed as u8);
                 if new_count == 0 {
-                    self.state = GameState::Playing;
-                    self.mode_state.start();
-                    self.last_fall = Instant::now();
-                    self.countdown_start = None;
+                    self.start_play();
                 } else if new_count != count {
                     self.state = GameState::Countdown(new_count);
                 }
@@ -184,22 +380,58 @@ impl Game {
         }
 
         // Update mode timer
-        self.mode_state.update();
+        self.mode_state.update(now);
 
         // Check for mode completion
-        if self.mode_state.is_complete(self.score.lines) {
+        let complete = match self.mode_state.custom_goal() {
+            Some(goal) => goal.is_met(self.mode_state.elapsed, self.score.lines, self.score.points, self.pieces_placed),
+            None => self.mode_state.is_complete(self.score.lines),
+        };
+        if complete {
             self.state = match self.mode_state.mode {
-                GameMode::Sprint => GameState::Victory,
+                GameMode::Sprint(_) | GameMode::Daily => GameState::Victory,
                 GameMode::Ultra => GameState::GameOver, // Time's up
                 GameMode::Marathon => GameState::Playing, // Never ends
                 GameMode::Versus => GameState::Playing, // Ends when opponent disconnects/loses
+                GameMode::Master => GameState::Victory, // Survived the credit roll
+                GameMode::Cheese(_) => GameState::Victory,
+                GameMode::Blitz => GameState::GameOver, // Time's up
+                GameMode::Custom if matches!(self.mode_state.custom_goal(), Some(Goal::Time(_))) => {
+                    GameState::GameOver
+                }
+                GameMode::Custom => GameState::Victory,
                 _ => GameState::Playing,
             };
+            if self.mode_state.mode == GameMode::Blitz {
+                let bonus = self.mode_state.lock_out(self.score.points, self.score.level);
+                self.score.points += bonus;
+                self.last_action = Some(format!("LOCK OUT +{}", bonus));
+            }
             if self.state != GameState::Playing {
                 return;
             }
         }
 
+        // Survival: raise garbage rows that are due
+        let elapsed = self.mode_state.elapsed;
+        if let Some(survival) = &mut self.mode_state.survival {
+            for _ in 0..survival.rows_due(elapsed) {
+                self.add_garbage_lines(1);
+            }
+            if self.state == GameState::GameOver {
+                return;
+            }
+        }
+
+        // Spawn the next piece once the entry delay is over
+        if let Some(spawn_time) = self.spawn_timer {
+            if now >= spawn_time {
+                self.spawn_timer = None;
+                self.spawn_next();
+            }
+            return;
+        }
+
         let Some(piece) = &self.current_piece else {
             return;
         };
@@ -215,22 +447,153 @@ impl Game {
         if on_ground {
             // Start or check lock timer
             if let Some(lock_start) = self.lock_timer {
-                if lock_start.elapsed() >= LOCK_DELAY {
+                if now - lock_start >= self.lock_delay() {
                     self.lock_piece();
                 }
             } else {
-                self.lock_timer = Some(Instant::now());
+                self.lock_timer = Some(now);
             }
         } else {
             // Not on ground, apply gravity
-            self.lock_timer = None;
-            let fall_speed = Duration::from_secs_f64(self.score.fall_speed());
-            if self.last_fall.elapsed() >= fall_speed {
-                if let Some(piece) = &mut self.current_piece {
-                    piece.move_down(&self.board);
-                }
-                self.last_fall = Instant::now();
+            // Step reset keeps the timer running until the piece steps down
+            if self.rules.lock_reset != LockReset::Step {
+                self.lock_timer = None;
             }
+            self.apply_gravity();
+        }
+    }
+
+    /// Current gravity in G, from the rules' curve and our level
+    pub fn gravity(&self) -> f64 {
+        let level = match &self.mode_state.master {
+            Some(master) => master.level,
+            None => self.score.level,
+        };
+        self.rules.gravity.at_level(level)
+    }
+
+    /// Lock delay from the rules, shortened at high Master levels
+    fn lock_delay(&self) -> Duration {
+        let lock_delay = self.rules.lock_delay();
+        match &self.mode_state.master {
+            Some(master) => lock_delay.min(master.timing().lock_delay),
+            None => lock_delay,
+        }
+    }
+
+    /// Delay before the next piece spawns after a lock, from the rules and
+    /// shortened at high Master levels
+    fn entry_delay(&self, lines_cleared: usize) -> Duration {
+        let (mut are, mut line_clear_delay) = (self.rules.are(), self.rules.line_clear_delay());
+        if let Some(master) = &self.mode_state.master {
+            let timing = master.timing();
+            are = are.min(timing.are);
+            line_clear_delay = line_clear_delay.min(timing.line_clear_delay);
+        }
+        if lines_cleared > 0 { are + line_clear_delay } else { are }
+    }
+
+    /// Whether locked blocks are hidden (during the Master credit roll)
+    pub fn stack_hidden(&self) -> bool {
+        self.mode_state.master.as_ref().is_some_and(|master| master.in_roll())
+    }
+
+    /// Let the piece fall one frame's worth of gravity, possibly several rows
+    fn apply_gravity(&mut self) {
+        let gravity = self.gravity();
+        if gravity >= TWENTY_G {
+            self.fall_progress = f64::INFINITY;
+        } else {
+            self.fall_progress += gravity;
+        }
+
+        while self.fall_progress >= 1.0 - GRAVITY_EPSILON {
+            let moved = self
+                .current_piece
+                .as_mut()
+                .is_some_and(|piece| piece.move_down(&self.board));
+            if !moved {
+                // Landed: whatever is left over doesn't carry to the next piece
+                self.fall_progress = 0.0;
+                return;
+            }
+            self.fall_progress -= 1.0;
+            self.track_lowest_row();
+        }
+    }
+
+    /// Queue an incoming attack; it enters the board on a later lock
+    pub fn queue_garbage(&mut self, lines: u8) {
+        if lines > 0 {
+            self.record(ReplayInput::Garbage(lines));
+        }
+        let now = self.clock();
+        self.garbage_queue.push(lines, now);
+    }
+
+    /// Take garbage lines to send to the opponent (resets to 0)
+    pub fn take_outgoing_garbage(&mut self) -> u8 {
+        std::mem::take(&mut self.outgoing_garbage)
+    }
+
+    /// Push garbage lines up from the bottom of the board (from an opponent)
+    pub fn add_garbage_lines(&mut self, lines: u8) {
+        if lines == 0 || matches!(self.state, GameState::GameOver | GameState::Victory) {
+            return;
+        }
+
+        // All lines from one attack share the same hole column
+        let hole_col = self.garbage_rng.gen_range(0..self.board.width());
+        let fits = self.board.add_garbage(lines as usize, hole_col);
+        self.garbage_added(fits);
+    }
+
+    /// Cheese Race: add garbage rows until the minimum is back on screen
+    fn refill_cheese(&mut self) {
+        let Some(cheese) = &mut self.mode_state.cheese else {
+            return;
+        };
+        let rows = cheese.rows_to_add(self.board.garbage_rows() as u32);
+        if rows == 0 {
+            return;
+        }
+
+        let mut fits = true;
+        for _ in 0..rows {
+            let hole_col = cheese.next_hole(&mut self.garbage_rng, self.board.width(), self.rules.cheese_messiness);
+            fits &= self.board.add_garbage(1, hole_col);
+        }
+        self.garbage_added(fits);
+    }
+
+    /// After garbage rows were pushed in: make room for the falling piece
+    /// and check for a top out
+    fn garbage_added(&mut self, fits: bool) {
+        // Push the falling piece up out of the new garbage
+        if let Some(piece) = &mut self.current_piece {
+            while !self.board.are_positions_valid(&piece.block_positions())
+                && piece.row < self.board.total_height() as i32
+            {
+                piece.row += 1;
+            }
+        }
+
+        // Top out if the stack was pushed into the buffer zone
+        if !fits || self.board.is_topped_out() {
+            self.top_out();
+        }
+    }
+
+    /// The stack topped out: game over, or a fresh board in endless modes
+    /// Returns true if the game is over
+    fn top_out(&mut self) -> bool {
+        if self.mode().endless() {
+            self.board = Board::with_size(self.board.width(), self.board.height());
+            self.last_action = Some("BOARD CLEARED".to_string());
+            false
+        } else {
+            self.state = GameState::GameOver;
+            true
         }
     }
 
@@ -254,9 +617,8 @@ impl Game {
         if let Some(piece) = &mut self.current_piece {
             if piece.move_down(&self.board) {
                 self.soft_drop_distance += 1;
-                self.last_fall = Instant::now();
-                // Reset lock timer if we moved down
-                self.lock_timer = None;
+                self.fall_progress = 0.0;
+                self.track_lowest_row();
             }
         }
     }
@@ -270,6 +632,13 @@ impl Game {
     }
 
     fn rotate(&mut self, direction: RotationDirection) {
+        if self.spawn_timer.is_some() {
+            if self.rules.irs {
+                self.initial_rotation = Some(direction);
+            }
+            return;
+        }
+
         if let Some(piece) = &mut self.current_piece {
             if piece.rotate(direction, &self.board) {
                 self.try_reset_lock();
@@ -278,6 +647,17 @@ impl Game {
     }
 
     fn hold(&mut self) {
+        if !self.rules.hold {
+            return;
+        }
+
+        if self.spawn_timer.is_some() {
+            if self.rules.ihs {
+                self.initial_hold = true;
+            }
+            return;
+        }
+
         if self.hold_used {
             return;
         }
@@ -288,15 +668,15 @@ impl Game {
 
         let next_piece = if let Some(held) = self.hold_piece.take() {
             self.hold_piece = Some(current.piece_type);
-            Piece::new(held)
+            self.new_piece(held)
         } else {
             self.hold_piece = Some(current.piece_type);
-            Piece::new(self.bag.next())
+            let next = self.bag.next();
+            self.new_piece(next)
         };
 
         // Check if new piece can spawn
-        if !self.board.are_positions_valid(&next_piece.block_positions()) {
-            self.state = GameState::GameOver;
+        if !self.board.are_positions_valid(&next_piece.block_positions()) && self.top_out() {
             return;
         }
 
@@ -305,20 +685,37 @@ impl Game {
         self.reset_piece_state();
     }
 
-    /// Try to reset lock delay (limited resets per piece)
+    /// Try to reset lock delay after a move or rotation
     fn try_reset_lock(&mut self) {
-        if let Some(piece) = &self.current_piece {
-            // Only reset if we're lower than before or haven't used all resets
-            if piece.row < self.lowest_row {
-                self.lowest_row = piece.row;
-                self.lock_resets = 0;
-            }
+        if self.track_lowest_row() || self.lock_timer.is_none() {
+            return;
+        }
 
-            if self.lock_resets < MAX_LOCK_RESETS && self.lock_timer.is_some() {
-                self.lock_timer = Some(Instant::now());
-                self.lock_resets += 1;
+        match self.rules.lock_reset {
+            LockReset::Step => {}
+            LockReset::Move => {
+                if self.lock_resets < self.rules.lock_reset_limit {
+                    self.lock_timer = Some(self.clock());
+                    self.lock_resets += 1;
+                }
             }
+            LockReset::Infinity => self.lock_timer = Some(self.clock()),
+        }
+    }
+
+    /// Note a new lowest row for the current piece, which restarts the lock
+    /// delay and refills the lock resets. Returns true if the piece stepped down
+    fn track_lowest_row(&mut self) -> bool {
+        let Some(piece) = &self.current_piece else {
+            return false;
+        };
+        if piece.row >= self.lowest_row {
+            return false;
         }
+        self.lowest_row = piece.row;
+        self.lock_resets = 0;
+        self.lock_timer = None;
+        true
     }
 
     /// Lock the current piece and spawn next
@@ -337,9 +734,13 @@ impl Game {
         // Detect T-spin before clearing lines
         let is_t_spin = self.detect_t_spin(&piece);
 
-        // Clear lines
+        // Clear lines, counting the garbage rows among them
+        let garbage_rows = self.board.garbage_rows();
         let lines_cleared = self.board.clear_lines();
         let all_clear = self.board.is_empty();
+        if let Some(cheese) = &mut self.mode_state.cheese {
+            cheese.cleared += (garbage_rows - self.board.garbage_rows()) as u32;
+        }
 
         // Track back-to-back before scoring updates it
         let was_back_to_back = self.score.back_to_back;
@@ -354,41 +755,203 @@ impl Game {
             self.last_action = Some(self.score.add_clear(clear_type, all_clear));
 
             // Store clear info for garbage calculation
-            self.last_clear_info = Some(ClearInfo {
+            let clear_info = ClearInfo {
                 lines: lines_cleared as u8,
                 is_tspin: is_t_spin.is_some(),
                 combo: self.score.combo,
                 back_to_back: was_back_to_back && (lines_cleared == 4 || is_t_spin.is_some()),
-            });
+            };
+
+            // Attack cancels incoming garbage first, the rest goes out
+            let attack = calculate_garbage(
+                clear_info.lines as u32,
+                clear_info.is_tspin,
+                clear_info.combo.max(0) as u32,
+                clear_info.back_to_back,
+            );
+            let sent = self.garbage_queue.cancel(attack);
+            self.outgoing_garbage = self.outgoing_garbage.saturating_add(sent);
+            self.garbage_sent += sent as u32;
+
+            self.last_clear_info = Some(clear_info);
         } else {
             self.score.reset_combo();
             self.last_action = None;
             self.last_clear_info = None;
         }
 
+        self.mode_state.record_splits(self.score.lines);
+
+        // Master: level up, and clear the board for the credit roll at 999
+        let elapsed = self.mode_state.elapsed;
+        if let Some(master) = &mut self.mode_state.master
+            && master.on_lock(lines_cleared as u32, self.soft_drop_distance, all_clear, elapsed)
+        {
+            self.board = Board::with_size(self.board.width(), self.board.height());
+        }
+
         // Flag that piece was locked (for multiplayer sync)
         self.piece_just_locked = true;
+        self.pieces_placed += 1;
+        if let Some(practice) = &mut self.mode_state.practice {
+            practice.on_lock();
+        }
 
-        // Spawn next piece
-        let next_type = self.bag.next();
-        let next_piece = Piece::new(next_type);
+        // Queued garbage only enters on a lock that clears nothing
+        if lines_cleared == 0 {
+            let now = self.clock();
+            for lines in self.garbage_queue.take_ready(now) {
+                self.add_garbage_lines(lines);
+            }
+            if self.state == GameState::GameOver {
+                return;
+            }
+        }
 
-        // Check for top out
-        if !self.board.are_positions_valid(&next_piece.block_positions()) {
-            self.state = GameState::GameOver;
+        // Cheese Race: top the garbage back up
+        self.refill_cheese();
+        if self.state == GameState::GameOver {
             return;
         }
 
         // Check for block out (any locked blocks above visible area)
-        for (row, _) in &positions {
-            if *row >= BOARD_HEIGHT as i32 {
-                self.state = GameState::GameOver;
-                return;
+        let height = self.board.height() as i32;
+        if positions.iter().any(|&(row, _)| row >= height) && self.top_out() {
+            return;
+        }
+
+        // Spawn next piece, after the entry delay if there is one
+        let entry_delay = self.entry_delay(lines_cleared);
+        if entry_delay.is_zero() {
+            self.spawn_next();
+        } else {
+            self.spawn_timer = Some(self.clock() + entry_delay);
+        }
+    }
+
+    /// Spawn the next piece from the bag, applying IHS/IRS from buttons
+    /// pressed during the entry delay or still held
+    fn spawn_next(&mut self) {
+        let mut next_type = self.bag.next();
+
+        // IHS: the spawning piece goes straight to hold
+        let held_hold = self.held.hold && self.rules.hold && self.rules.ihs;
+        let initial_hold = std::mem::take(&mut self.initial_hold) || held_hold;
+        if initial_hold {
+            next_type = match self.hold_piece.replace(next_type) {
+                Some(held) => held,
+                None => self.bag.next(),
+            };
+        }
+
+        // IRS: spawn already rotated if that fits, otherwise spawn normally
+        let mut next_piece = self.new_piece(next_type);
+        let held_rotation = self.held.rotation.filter(|_| self.rules.irs);
+        if let Some(direction) = self.initial_rotation.take().or(held_rotation) {
+            let mut rotated = next_piece.clone();
+            rotated.rotation = next_piece.rotation.rotated(direction);
+            if self.board.are_positions_valid(&rotated.block_positions()) {
+                next_piece = rotated;
             }
         }
 
+        // Check for top out
+        if !self.board.are_positions_valid(&next_piece.block_positions()) && self.top_out() {
+            return;
+        }
+
         self.current_piece = Some(next_piece);
         self.reset_piece_state();
+        self.hold_used = initial_hold;
+        self.snapshot_spawn();
+
+        let elapsed = self.mode_state.elapsed;
+        if let Some(master) = &mut self.mode_state.master {
+            master.on_spawn(elapsed);
+        }
+    }
+
+    /// Practice: remember the state as the current piece spawned
+    fn snapshot_spawn(&mut self) {
+        let (Some(practice), Some(piece)) = (&mut self.mode_state.practice, &self.current_piece) else {
+            return;
+        };
+        practice.spawned = Some(Snapshot {
+            board: self.board.clone(),
+            score: self.score.clone(),
+            hold_piece: self.hold_piece,
+            piece: piece.piece_type,
+            bag: self.bag.clone(),
+            pieces_placed: self.pieces_placed,
+        });
+    }
+
+    /// Practice: go back to a snapshot, with its piece freshly spawned
+    fn restore(&mut self, snapshot: Snapshot, label: &str) {
+        self.board = snapshot.board.clone();
+        self.current_piece = Some(self.new_piece(snapshot.piece));
+        self.score = snapshot.score.clone();
+        self.hold_piece = snapshot.hold_piece;
+        self.bag = snapshot.bag.clone();
+        self.pieces_placed = snapshot.pieces_placed;
+        self.spawn_timer = None;
+        self.initial_rotation = None;
+        self.initial_hold = false;
+        self.reset_piece_state();
+        self.last_action = Some(label.to_string());
+        if let Some(practice) = &mut self.mode_state.practice {
+            practice.spawned = Some(snapshot);
+        }
+    }
+
+    fn practice_undo(&mut self) {
+        // Nothing to go back from until the next piece is out
+        if self.spawn_timer.is_some() {
+            return;
+        }
+        if let Some(snapshot) = self.mode_state.practice.as_mut().and_then(PracticeState::undo) {
+            self.restore(snapshot, "UNDO");
+        }
+    }
+
+    fn practice_redo(&mut self) {
+        if self.spawn_timer.is_some() {
+            return;
+        }
+        if let Some(snapshot) = self.mode_state.practice.as_mut().and_then(PracticeState::redo) {
+            self.restore(snapshot, "REDO");
+        }
+    }
+
+    fn practice_reset(&mut self) {
+        if let Some(snapshot) = self.mode_state.practice.as_mut().and_then(PracticeState::reset) {
+            self.restore(snapshot, "RESET");
+        }
+    }
+
+    /// Practice: swap the next piece for the following type (I, O, T, S,
+    /// Z, J, L, then around again)
+    fn cycle_next(&mut self) {
+        if self.mode_state.practice.is_none() {
+            return;
+        }
+        let all = TetrominoType::all();
+        let Some(&next) = self.bag.preview(1).first() else {
+            return;
+        };
+        let index = all.iter().position(|&piece| piece == next).unwrap_or(0);
+        self.bag.choose_next(all[(index + 1) % all.len()]);
+
+        // Undoing this piece keeps the choice
+        let bag = self.bag.clone();
+        if let Some(spawned) = self.mode_state.practice.as_mut().and_then(|practice| practice.spawned.as_mut()) {
+            spawned.bag = bag;
+        }
+    }
+
+    /// Create a piece at its spawn position using our rotation system
+    fn new_piece(&self, piece_type: TetrominoType) -> Piece {
+        Piece::spawn(piece_type, self.rules.rotation_system, &self.board)
     }
 
     /// Reset per-piece state
@@ -396,14 +959,14 @@ impl Game {
         self.hold_used = false;
         self.lock_timer = None;
         self.lock_resets = 0;
-        self.lowest_row = i32::MAX;
-        self.last_fall = Instant::now();
+        self.lowest_row = self.current_piece.as_ref().map_or(i32::MAX, |piece| piece.row);
+        self.fall_progress = 0.0;
         self.soft_drop_distance = 0;
     }
 
     /// Detect T-spin (returns Some(true) for T-spin, Some(false) for mini T-spin, None for no T-spin)
     fn detect_t_spin(&self, piece: &Piece) -> Option<bool> {
-        if !piece.is_t_piece() || piece.last_kick == 0 {
+        if !piece.is_t_piece() || piece.last_kick == 0 || !self.rules.rotation_system.system().t_spins() {
             return None;
         }
 
@@ -421,7 +984,7 @@ impl Game {
             .map(|&(r, c)| {
                 self.board
                     .get(r, c)
-                    .map(|cell| matches!(cell, Cell::Filled(_)))
+                    .map(|cell| cell.is_filled())
                     .unwrap_or(true) // Out of bounds counts as filled
             })
             .collect();
@@ -441,8 +1004,9 @@ impl Game {
             if filled_corners[front_a] && filled_corners[front_b] {
                 Some(true)
             } else {
-                // Special case: kick 5 always counts as full T-spin
-                if piece.last_kick == 5 {
+                // Special case: SRS kick 5 always counts as full T-spin
+                // (180 kicks have no such kick)
+                if piece.last_kick == 5 && piece.last_rotation != Some(RotationDirection::Half) {
                     Some(true)
                 } else {
                     Some(false)
@@ -459,3 +1023,416 @@ impl Default for Game {
         Self::new(GameMode::Marathon)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{Cell, BOARD_HEIGHT, BOARD_WIDTH};
+    use crate::gravity::GravityCurve;
+    use crate::tetromino::Rotation;
+
+    /// Tick through the 3 second countdown
+    fn start(game: &mut Game) {
+        for _ in 0..3 * TICK_RATE {
+            game.tick();
+        }
+        assert_eq!(game.state, GameState::Playing);
+        assert_eq!(game.play_frames(), Some(0));
+    }
+
+    #[test]
+    fn test_advance_runs_whole_ticks() {
+        let mut game = Game::with_seed(GameMode::Marathon, 1);
+        game.advance(TICK / 2);
+        assert_eq!(game.clock(), Duration::ZERO);
+        game.advance(TICK / 2);
+        assert_eq!(game.clock(), frames_to_duration(1));
+    }
+
+    #[test]
+    fn test_gravity_level_one() {
+        let mut game = Game::with_seed(GameMode::Marathon, 1);
+        start(&mut game);
+        let row = game.current_piece.as_ref().unwrap().row;
+
+        // Level 1 falls one row per second
+        for _ in 0..TICK_RATE - 1 {
+            game.tick();
+        }
+        assert_eq!(game.current_piece.as_ref().unwrap().row, row);
+        game.tick();
+        assert_eq!(game.current_piece.as_ref().unwrap().row, row - 1);
+    }
+
+    #[test]
+    fn test_twenty_g() {
+        let rules = Rules {
+            gravity: GravityCurve::Fixed(TWENTY_G),
+            ..Rules::default()
+        };
+        let mut game = Game::with_rules(GameMode::Marathon, 1, rules);
+        start(&mut game);
+        game.tick();
+        // Straight to the floor in a single frame
+        let piece = game.current_piece.as_ref().unwrap();
+        assert_eq!(piece.row, piece.ghost_row(&game.board));
+    }
+
+    #[test]
+    fn test_fractional_gravity() {
+        let rules = Rules {
+            gravity: GravityCurve::Fixed(2.5),
+            ..Rules::default()
+        };
+        let mut game = Game::with_rules(GameMode::Marathon, 1, rules);
+        start(&mut game);
+        let row = game.current_piece.as_ref().unwrap().row;
+        game.tick();
+        game.tick();
+        assert_eq!(game.current_piece.as_ref().unwrap().row, row - 5);
+    }
+
+    #[test]
+    fn test_lock_delay() {
+        let mut game = Game::with_seed(GameMode::Marathon, 1);
+        start(&mut game);
+        for _ in 0..40 {
+            game.process_action(Action::SoftDrop);
+        }
+
+        // First tick on the ground starts the timer, then it locks after 500 ms
+        game.tick();
+        let lock_frames = duration_to_frames(Rules::default().lock_delay());
+        for _ in 0..lock_frames - 1 {
+            game.tick();
+        }
+        assert_eq!(game.pieces_placed, 0);
+        game.tick();
+        assert_eq!(game.pieces_placed, 1);
+    }
+
+    /// Drop the current piece to the floor, tick once to start the lock
+    /// timer, then wiggle it left and right for `frames` ticks
+    fn wiggle_on_floor(game: &mut Game, frames: u64) {
+        for _ in 0..40 {
+            game.process_action(Action::SoftDrop);
+        }
+        game.tick();
+        for i in 0..frames {
+            let action = if i % 2 == 0 { Action::MoveLeft } else { Action::MoveRight };
+            game.process_action(action);
+            game.tick();
+        }
+    }
+
+    #[test]
+    fn test_step_reset_ignores_moves() {
+        let rules = Rules {
+            lock_reset: LockReset::Step,
+            ..Rules::default()
+        };
+        let mut game = Game::with_rules(GameMode::Marathon, 1, rules);
+        start(&mut game);
+        let lock_frames = duration_to_frames(game.rules.lock_delay());
+        wiggle_on_floor(&mut game, lock_frames);
+        assert_eq!(game.pieces_placed, 1);
+    }
+
+    #[test]
+    fn test_move_reset_limit() {
+        let mut game = Game::with_seed(GameMode::Marathon, 1);
+        start(&mut game);
+        // Each move resets the timer, so it holds on well past the lock delay
+        wiggle_on_floor(&mut game, 40);
+        assert_eq!(game.pieces_placed, 0);
+        // Until the resets run out
+        let lock_frames = duration_to_frames(game.rules.lock_delay());
+        wiggle_on_floor(&mut game, lock_frames);
+        assert_eq!(game.pieces_placed, 1);
+    }
+
+    #[test]
+    fn test_infinity_reset() {
+        let rules = Rules {
+            lock_reset: LockReset::Infinity,
+            ..Rules::default()
+        };
+        let mut game = Game::with_rules(GameMode::Marathon, 1, rules);
+        start(&mut game);
+        wiggle_on_floor(&mut game, 600);
+        assert_eq!(game.pieces_placed, 0);
+    }
+
+    #[test]
+    fn test_entry_delay() {
+        let rules = Rules {
+            are_ms: 500,
+            ..Rules::default()
+        };
+        let mut game = Game::with_rules(GameMode::Marathon, 1, rules);
+        start(&mut game);
+        game.process_action(Action::HardDrop);
+        assert!(game.current_piece.is_none());
+
+        // Input during the delay does nothing
+        game.process_action(Action::HardDrop);
+        assert_eq!(game.pieces_placed, 1);
+
+        for _ in 0..duration_to_frames(Duration::from_millis(500)) {
+            game.tick();
+        }
+        assert!(game.current_piece.is_some());
+    }
+
+    /// Hard drop with a 500 ms entry delay and run `action` during it
+    fn act_during_entry_delay(action: Action) -> Game {
+        let rules = Rules {
+            are_ms: 500,
+            ..Rules::default()
+        };
+        let mut game = Game::with_rules(GameMode::Marathon, 1, rules);
+        start(&mut game);
+        game.process_action(Action::HardDrop);
+        game.process_action(action);
+        for _ in 0..duration_to_frames(Duration::from_millis(500)) {
+            game.tick();
+        }
+        game
+    }
+
+    #[test]
+    fn test_initial_rotation() {
+        let game = act_during_entry_delay(Action::RotateCW);
+        assert_eq!(game.current_piece.as_ref().map(|piece| piece.rotation), Some(Rotation::East));
+    }
+
+    #[test]
+    fn test_initial_hold() {
+        let next = Game::with_seed(GameMode::Marathon, 1).preview()[0];
+        let game = act_during_entry_delay(Action::Hold);
+        // The piece that would have spawned went to hold instead
+        assert_eq!(game.hold_piece, Some(next));
+        assert_ne!(game.current_piece.as_ref().map(|piece| piece.piece_type), Some(next));
+        assert!(game.hold_used);
+    }
+
+    #[test]
+    fn test_held_buttons_without_entry_delay() {
+        let next = Game::with_seed(GameMode::Marathon, 1).preview()[..2].to_vec();
+        let mut game = Game::with_seed(GameMode::Marathon, 1);
+        start(&mut game);
+        assert_eq!(game.rules().are_ms, 0);
+
+        // Rotate held across the lock: the next piece spawns rotated
+        game.set_held(HeldInput {
+            rotation: Some(RotationDirection::Clockwise),
+            hold: false,
+        });
+        game.process_action(Action::HardDrop);
+        let piece = game.current_piece.as_ref().unwrap();
+        assert_eq!((piece.piece_type, piece.rotation), (next[0], Rotation::East));
+
+        // Hold held across the lock: the next piece goes straight to hold
+        game.set_held(HeldInput {
+            rotation: None,
+            hold: true,
+        });
+        game.process_action(Action::HardDrop);
+        assert_eq!(game.hold_piece, Some(next[1]));
+        assert!(game.hold_used);
+        assert!(game.recorded_events().iter().any(|event| matches!(event.input, ReplayInput::Held(_))));
+    }
+
+    #[test]
+    fn test_tspin_after_180() {
+        let mut game = Game::with_seed(GameMode::Marathon, 1);
+        start(&mut game);
+
+        // T slot at the bottom with all four corners filled
+        for col in 0..BOARD_WIDTH as i32 {
+            if col != 4 {
+                game.board.set(0, col, Cell::Garbage);
+            }
+            if !(3..=5).contains(&col) {
+                game.board.set(1, col, Cell::Garbage);
+            }
+        }
+        game.board.set(2, 3, Cell::Garbage);
+        game.board.set(2, 5, Cell::Garbage);
+
+        let mut piece = Piece::new(TetrominoType::T);
+        piece.row = 1;
+        piece.col = 4;
+        game.current_piece = Some(piece);
+
+        game.process_action(Action::Rotate180);
+        game.process_action(Action::HardDrop);
+        assert_eq!(game.last_clear_info.as_ref().map(|info| info.is_tspin), Some(true));
+        assert_eq!(game.score.lines, 2);
+    }
+
+    #[test]
+    fn test_narrow_tall_board() {
+        let rules = Rules {
+            board_width: 4,
+            board_height: 40,
+            ..Rules::default()
+        };
+        let mut game = Game::with_rules(GameMode::Marathon, 1, rules);
+        start(&mut game);
+        assert_eq!((game.board.width(), game.board.height()), (4, 40));
+
+        // Pieces spawn above the tall stack and lock inside the narrow well
+        for _ in 0..5 {
+            game.process_action(Action::HardDrop);
+        }
+        assert_eq!(game.pieces_placed, 5);
+        assert_eq!(game.state, GameState::Playing);
+    }
+
+    #[test]
+    fn test_cheese_race() {
+        let mut game = Game::with_seed(GameMode::Cheese(11), 1);
+        start(&mut game);
+        assert_eq!(game.board.garbage_rows(), 10);
+        assert_eq!(game.mode_state.garbage_remaining(), Some(11));
+
+        // Plug the bottom row's hole and lock a piece to clear it
+        let hole = (0..BOARD_WIDTH as i32)
+            .find(|&col| game.board.get(0, col) == Some(Cell::Empty))
+            .unwrap();
+        game.board.set(0, hole, Cell::Garbage);
+        game.process_action(Action::HardDrop);
+        assert_eq!(game.mode_state.garbage_remaining(), Some(10));
+        // Topped back up with the last of the race's garbage
+        assert_eq!(game.board.garbage_rows(), 10);
+
+        // Digging out the rest wins
+        game.board = Board::new();
+        game.mode_state.cheese.as_mut().unwrap().cleared = 11;
+        game.tick();
+        assert_eq!(game.state, GameState::Victory);
+    }
+
+    #[test]
+    fn test_survival_garbage_rises() {
+        let mut game = Game::with_seed(GameMode::Survival, 1);
+        start(&mut game);
+        game.current_piece = None;
+        for _ in 0..duration_to_frames(Duration::from_secs(4)) {
+            game.tick();
+        }
+        assert_eq!(game.board.garbage_rows(), 1);
+
+        // Rows keep coming until the stack tops out
+        while game.state == GameState::Playing {
+            game.tick();
+        }
+        assert_eq!(game.state, GameState::GameOver);
+        assert!(game.board.garbage_rows() >= BOARD_HEIGHT);
+    }
+
+    #[test]
+    fn test_zen_never_tops_out() {
+        let mut game = Game::with_seed(GameMode::Zen, 1);
+        start(&mut game);
+        for _ in 0..200 {
+            game.process_action(Action::HardDrop);
+        }
+        assert_eq!(game.state, GameState::Playing);
+        assert_eq!(game.pieces_placed, 200);
+        assert!(!game.board.is_topped_out());
+    }
+
+    #[test]
+    fn test_blitz_lock_out_bonus() {
+        let mut game = Game::with_seed(GameMode::Blitz, 1);
+        start(&mut game);
+        game.current_piece = None;
+        game.score.points = 1000;
+        game.score.level = 3;
+        while game.state == GameState::Playing {
+            game.tick();
+        }
+        assert_eq!(game.state, GameState::GameOver);
+        assert_eq!(game.mode_state.elapsed, Duration::from_secs(120));
+        assert_eq!(game.score.points, 1200);
+    }
+
+    #[test]
+    fn test_custom_mode() {
+        let custom = CustomMode {
+            name: "Three pieces".to_string(),
+            goal: Goal::Pieces(3),
+            starting_level: 7,
+            preview_count: 2,
+            hold: false,
+            ..CustomMode::default()
+        };
+        let mut game = Game::with_custom(&custom, 1, custom.rules());
+        start(&mut game);
+        assert_eq!(game.mode_name(), "Three pieces");
+        assert_eq!(game.score.level, 7);
+        assert_eq!(game.preview().len(), 2);
+
+        game.process_action(Action::Hold);
+        assert_eq!(game.hold_piece, None);
+
+        for _ in 0..3 {
+            game.process_action(Action::HardDrop);
+        }
+        game.tick();
+        assert_eq!(game.state, GameState::Victory);
+    }
+
+    #[test]
+    fn test_daily_keeps_its_day() {
+        let game = Game::with_daily(20_743);
+        assert_eq!(game.mode_state.day, Some(20_743));
+        assert_eq!(game.seed(), daily::seed(20_743));
+        assert_eq!(game.mode_state.target_lines, daily::DAILY_LINES);
+    }
+
+    #[test]
+    fn test_practice_undo_redo() {
+        let mut game = Game::with_seed(GameMode::Practice, 8);
+        start(&mut game);
+        assert_eq!(game.gravity(), 0.0);
+
+        let first = game.current_piece.as_ref().unwrap().piece_type;
+        game.process_action(Action::CycleNext);
+        let chosen = game.preview()[0];
+        game.process_action(Action::HardDrop);
+        assert_eq!(game.current_piece.as_ref().unwrap().piece_type, chosen);
+        game.process_action(Action::HardDrop);
+        let placed = game.board.clone();
+        assert_eq!(game.pieces_placed, 2);
+
+        game.process_action(Action::Undo);
+        game.process_action(Action::Undo);
+        assert_eq!(game.pieces_placed, 0);
+        assert_eq!(game.current_piece.as_ref().unwrap().piece_type, first);
+        assert!(game.board.is_empty());
+        // The chosen piece is still next
+        assert_eq!(game.preview()[0], chosen);
+
+        game.process_action(Action::Redo);
+        game.process_action(Action::Redo);
+        assert_eq!(game.pieces_placed, 2);
+        for row in 0..BOARD_HEIGHT as i32 {
+            for col in 0..10 {
+                assert_eq!(game.board.get(row, col), placed.get(row, col));
+            }
+        }
+
+        // A new placement drops what was undone
+        game.process_action(Action::Undo);
+        game.process_action(Action::HardDrop);
+        game.process_action(Action::Redo);
+        assert_eq!(game.pieces_placed, 2);
+
+        game.process_action(Action::Reset);
+        assert_eq!(game.pieces_placed, 0);
+        assert!(game.board.is_empty());
+    }
+}
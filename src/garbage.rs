@@ -0,0 +1,179 @@
+//! Garbage attack calculation and the incoming garbage queue
+//!
+//! Incoming garbage waits in a queue before entering the board. Lines the
+//! player clears in the meantime cancel queued garbage first, and queued
+//! garbage only enters on a lock that clears nothing.
+
+use std::time::Duration;
+
+/// Default delay before queued garbage may enter the board
+pub const DEFAULT_GARBAGE_DELAY: Duration = Duration::from_millis(500);
+
+/// Calculate garbage lines to send based on lines cleared
+/// Standard guideline:
+/// - Single: 0 garbage
+/// - Double: 1 garbage
+/// - Triple: 2 garbage
+/// - Tetris: 4 garbage
+/// - T-spin single: 2 garbage
+/// - T-spin double: 4 garbage
+/// - T-spin triple: 6 garbage
+/// - Back-to-back bonus: +1 garbage
+/// - Combo bonus: +combo count
+pub fn calculate_garbage(lines: u32, is_tspin: bool, combo: u32, back_to_back: bool) -> u8 {
+    let base = if is_tspin {
+        match lines {
+            1 => 2,
+            2 => 4,
+            3 => 6,
+            _ => 0,
+        }
+    } else {
+        match lines {
+            1 => 0,
+            2 => 1,
+            3 => 2,
+            4 => 4,
+            _ => 0,
+        }
+    };
+
+    let b2b_bonus = if back_to_back && (lines == 4 || is_tspin) { 1 } else { 0 };
+    let combo_bonus = if combo > 0 { combo.min(10) } else { 0 };
+
+    (base + b2b_bonus + combo_bonus as u8).min(12) // Cap at 12 lines
+}
+
+/// A single received attack waiting to enter the board
+#[derive(Debug, Clone, Copy)]
+pub struct IncomingGarbage {
+    /// Number of garbage lines in this attack
+    pub lines: u8,
+    /// Game clock time when the attack was received
+    pub received: Duration,
+}
+
+/// Queue of incoming garbage, oldest attack first
+#[derive(Debug, Clone)]
+pub struct GarbageQueue {
+    entries: Vec<IncomingGarbage>,
+    /// How long an attack waits before it may enter the board
+    delay: Duration,
+}
+
+impl Default for GarbageQueue {
+    fn default() -> Self {
+        Self::new(DEFAULT_GARBAGE_DELAY)
+    }
+}
+
+impl GarbageQueue {
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            entries: Vec::new(),
+            delay,
+        }
+    }
+
+    /// Change the entry delay for attacks
+    pub fn set_delay(&mut self, delay: Duration) {
+        self.delay = delay;
+    }
+
+    /// Get the entry delay
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// Queue an attack received at game clock time `now`
+    pub fn push(&mut self, lines: u8, now: Duration) {
+        if lines > 0 {
+            self.entries.push(IncomingGarbage {
+                lines,
+                received: now,
+            });
+        }
+    }
+
+    /// Cancel queued garbage with an outgoing attack (oldest first)
+    /// Returns the part of the attack left over to send to the opponent
+    pub fn cancel(&mut self, mut attack: u8) -> u8 {
+        while attack > 0 {
+            let Some(first) = self.entries.first_mut() else {
+                break;
+            };
+            let cancelled = attack.min(first.lines);
+            first.lines -= cancelled;
+            attack -= cancelled;
+            if first.lines == 0 {
+                self.entries.remove(0);
+            }
+        }
+        attack
+    }
+
+    /// Remove and return every attack whose entry delay has passed by `now`
+    pub fn take_ready(&mut self, now: Duration) -> Vec<u8> {
+        let delay = self.delay;
+        let ready = self
+            .entries
+            .iter()
+            .take_while(|entry| now.saturating_sub(entry.received) >= delay)
+            .count();
+        self.entries.drain(..ready).map(|entry| entry.lines).collect()
+    }
+
+    /// Time left at `now` before an attack may enter the board (zero when ready)
+    pub fn time_until_ready(&self, entry: &IncomingGarbage, now: Duration) -> Duration {
+        (entry.received + self.delay).saturating_sub(now)
+    }
+
+    /// Queued attacks, oldest first
+    pub fn entries(&self) -> &[IncomingGarbage] {
+        &self.entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total(queue: &GarbageQueue) -> u32 {
+        queue.entries().iter().map(|entry| entry.lines as u32).sum()
+    }
+
+    #[test]
+    fn test_garbage_table() {
+        assert_eq!(calculate_garbage(1, false, 0, false), 0);
+        assert_eq!(calculate_garbage(4, false, 0, false), 4);
+        assert_eq!(calculate_garbage(4, false, 0, true), 5);
+        assert_eq!(calculate_garbage(2, true, 0, false), 4);
+    }
+
+    #[test]
+    fn test_cancel_oldest_first() {
+        let mut queue = GarbageQueue::default();
+        queue.push(2, Duration::ZERO);
+        queue.push(3, Duration::ZERO);
+
+        // Cancels the first attack entirely and one line of the second
+        assert_eq!(queue.cancel(3), 0);
+        assert_eq!(queue.entries().len(), 1);
+        assert_eq!(total(&queue), 2);
+
+        // Leftover attack goes to the opponent
+        assert_eq!(queue.cancel(5), 3);
+        assert_eq!(total(&queue), 0);
+    }
+
+    #[test]
+    fn test_entry_delay() {
+        let mut queue = GarbageQueue::new(Duration::from_secs(1));
+        queue.push(4, Duration::from_secs(10));
+        assert!(queue.take_ready(Duration::from_millis(10_500)).is_empty());
+        assert_eq!(total(&queue), 4);
+
+        assert_eq!(queue.take_ready(Duration::from_secs(11)), vec![4]);
+        assert_eq!(total(&queue), 0);
+    }
+}
@@ -0,0 +1,76 @@
+//! Gravity: how fast pieces fall
+//!
+//! Gravity is measured in G, rows fallen per frame (1/60 s). 1/60 G falls
+//! one row per second, and 20G drops pieces straight to the floor.
+
+use serde::{Deserialize, Serialize};
+
+/// Gravity at or above this drops the piece to the floor instantly
+pub const TWENTY_G: f64 = 20.0;
+
+/// How gravity changes with level
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum GravityCurve {
+    /// Guideline formula, topping out at level 20
+    #[default]
+    Guideline,
+    /// The same gravity at every level
+    Fixed(f64),
+    /// (level, G) steps: each applies from its level until the next step
+    Table(Vec<(u32, f64)>),
+}
+
+impl GravityCurve {
+    /// TGM's level → gravity table, reaching 20G at level 500
+    pub fn master() -> Self {
+        // Internal gravity in 1/256 G
+        const STEPS: [(u32, u32); 30] = [
+            (0, 4), (30, 6), (35, 8), (40, 10), (50, 12), (60, 16), (70, 32), (80, 48),
+            (90, 64), (100, 80), (120, 96), (140, 112), (160, 128), (170, 144), (200, 4),
+            (220, 32), (230, 64), (233, 96), (236, 128), (239, 160), (243, 192), (247, 224),
+            (251, 256), (300, 512), (330, 768), (360, 1024), (400, 1280), (420, 1024),
+            (450, 768), (500, 5120),
+        ];
+        GravityCurve::Table(STEPS.iter().map(|&(level, internal)| (level, internal as f64 / 256.0)).collect())
+    }
+
+    /// Gravity in G at a level
+    pub fn at_level(&self, level: u32) -> f64 {
+        match self {
+            GravityCurve::Guideline => {
+                // Seconds per row from the guideline formula
+                let level = level.clamp(1, 20) as f64;
+                let seconds = (0.8 - ((level - 1.0) * 0.007)).powf(level - 1.0);
+                1.0 / (seconds * 60.0)
+            }
+            GravityCurve::Fixed(gravity) => *gravity,
+            GravityCurve::Table(steps) => steps
+                .iter()
+                .take_while(|&&(from, _)| from <= level)
+                .last()
+                .or(steps.first())
+                .map_or(0.0, |&(_, gravity)| gravity),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guideline_level_one() {
+        assert!((GravityCurve::Guideline.at_level(1) - 1.0 / 60.0).abs() < 1e-12);
+        // Capped at level 20
+        assert_eq!(GravityCurve::Guideline.at_level(25), GravityCurve::Guideline.at_level(20));
+    }
+
+    #[test]
+    fn test_table_steps() {
+        let curve = GravityCurve::master();
+        assert_eq!(curve.at_level(0), 4.0 / 256.0);
+        assert_eq!(curve.at_level(34), 6.0 / 256.0);
+        assert_eq!(curve.at_level(200), 4.0 / 256.0);
+        assert_eq!(curve.at_level(999), TWENTY_G);
+    }
+}
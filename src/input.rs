@@ -3,8 +3,9 @@
 //! Uses a polling-based approach that doesn't rely on key release events,
 //! which are unreliable on Linux terminals.
 
-use crate::game::Action;
-use crate::settings::Settings;
+use tetrs::game::{Action, HeldInput};
+use tetrs::settings::Settings;
+use tetrs::tetromino::RotationDirection;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use std::time::{Duration, Instant};
 
@@ -17,6 +18,11 @@ pub struct InputHandler {
     left_state: Option<KeyPressState>,
     right_state: Option<KeyPressState>,
     down_state: Option<KeyPressState>,
+    /// Rotate and hold keys down, for IRS and IHS
+    rotate_state: Option<(RotationDirection, ButtonState)>,
+    hold_state: Option<ButtonState>,
+    /// Whether the terminal sends key releases (most Linux terminals don't)
+    releases_reported: bool,
     /// Key bindings
     bindings: KeyBindings,
     /// DAS duration
@@ -33,6 +39,25 @@ struct KeyPressState {
     last_arr: Option<Instant>,
 }
 
+#[derive(Debug, Clone)]
+struct ButtonState {
+    code: KeyCode,
+    last_seen: Instant,
+    /// Key repeat seen, so the key is held rather than tapped
+    repeating: bool,
+}
+
+impl ButtonState {
+    /// Track a press of `code`, a repeat if it was already down
+    fn press(state: Option<&ButtonState>, code: KeyCode, now: Instant) -> ButtonState {
+        ButtonState {
+            code,
+            last_seen: now,
+            repeating: state.is_some_and(|state| state.code == code),
+        }
+    }
+}
+
 /// Key bindings configuration - supports multiple keys per action
 #[derive(Debug, Clone)]
 pub struct KeyBindings {
@@ -42,9 +67,14 @@ pub struct KeyBindings {
     pub hard_drop: Vec<KeyCode>,
     pub rotate_cw: Vec<KeyCode>,
     pub rotate_ccw: Vec<KeyCode>,
+    pub rotate_180: Vec<KeyCode>,
     pub hold: Vec<KeyCode>,
     pub pause: Vec<KeyCode>,
     pub quit: Vec<KeyCode>,
+    pub undo: Vec<KeyCode>,
+    pub redo: Vec<KeyCode>,
+    pub reset: Vec<KeyCode>,
+    pub next_piece: Vec<KeyCode>,
 }
 
 impl KeyBindings {
@@ -81,9 +111,14 @@ impl KeyBindings {
             hard_drop: Self::parse_keys(&settings.keys.hard_drop),
             rotate_cw: Self::parse_keys(&settings.keys.rotate_cw),
             rotate_ccw: Self::parse_keys(&settings.keys.rotate_ccw),
+            rotate_180: Self::parse_keys(&settings.keys.rotate_180),
             hold: Self::parse_keys(&settings.keys.hold),
             pause: Self::parse_keys(&settings.keys.pause),
             quit: Self::parse_keys(&settings.keys.quit),
+            undo: Self::parse_keys(&settings.keys.undo),
+            redo: Self::parse_keys(&settings.keys.redo),
+            reset: Self::parse_keys(&settings.keys.reset),
+            next_piece: Self::parse_keys(&settings.keys.next_piece),
         }
     }
 
@@ -98,9 +133,14 @@ impl Default for KeyBindings {
             hard_drop: vec![KeyCode::Char(' ')],
             rotate_cw: vec![KeyCode::Up, KeyCode::Char('x')],
             rotate_ccw: vec![KeyCode::Char('z')],
+            rotate_180: vec![KeyCode::Char('a')],
             hold: vec![KeyCode::Char('c')],
             pause: vec![KeyCode::Char('p'), KeyCode::Esc],
             quit: vec![KeyCode::Char('q')],
+            undo: vec![KeyCode::Char('u')],
+            redo: vec![KeyCode::Char('y')],
+            reset: vec![KeyCode::Char('r')],
+            next_piece: vec![KeyCode::Char('n')],
         }
     }
 }
@@ -111,6 +151,9 @@ impl InputHandler {
             left_state: None,
             right_state: None,
             down_state: None,
+            rotate_state: None,
+            hold_state: None,
+            releases_reported: false,
             bindings: KeyBindings::default(),
             das: Duration::from_millis(170),
             arr: Duration::from_millis(50),
@@ -123,6 +166,9 @@ impl InputHandler {
             left_state: None,
             right_state: None,
             down_state: None,
+            rotate_state: None,
+            hold_state: None,
+            releases_reported: false,
             bindings: KeyBindings::from_settings(settings),
             das: Duration::from_millis(settings.gameplay.das_ms),
             arr: Duration::from_millis(settings.gameplay.arr_ms),
@@ -188,22 +234,49 @@ impl InputHandler {
             actions.push(Action::HardDrop);
         } else if self.bindings.rotate_cw.contains(&code) {
             actions.push(Action::RotateCW);
+            self.press_rotate(RotationDirection::Clockwise, code, now);
         } else if self.bindings.rotate_ccw.contains(&code) {
             actions.push(Action::RotateCCW);
+            self.press_rotate(RotationDirection::CounterClockwise, code, now);
+        } else if self.bindings.rotate_180.contains(&code) {
+            actions.push(Action::Rotate180);
+            self.press_rotate(RotationDirection::Half, code, now);
         } else if self.bindings.hold.contains(&code) {
             actions.push(Action::Hold);
+            self.hold_state = Some(ButtonState::press(self.hold_state.as_ref(), code, now));
         } else if self.bindings.pause.contains(&code) {
             actions.push(Action::Pause);
         } else if self.bindings.quit.contains(&code) {
             actions.push(Action::Quit);
+        } else if self.bindings.undo.contains(&code) {
+            actions.push(Action::Undo);
+        } else if self.bindings.redo.contains(&code) {
+            actions.push(Action::Redo);
+        } else if self.bindings.reset.contains(&code) {
+            actions.push(Action::Reset);
+        } else if self.bindings.next_piece.contains(&code) {
+            actions.push(Action::CycleNext);
         }
 
         actions
     }
 
+    fn press_rotate(&mut self, direction: RotationDirection, code: KeyCode, now: Instant) {
+        let state = ButtonState::press(self.rotate_state.as_ref().map(|(_, state)| state), code, now);
+        self.rotate_state = Some((direction, state));
+    }
+
     /// Handle a key release event (may not be called on Linux)
     pub fn key_up(&mut self, key: KeyEvent) {
         let code = normalize_key(key.code);
+        self.releases_reported = true;
+
+        if self.rotate_state.as_ref().is_some_and(|(_, state)| state.code == code) {
+            self.rotate_state = None;
+        }
+        if self.hold_state.as_ref().is_some_and(|state| state.code == code) {
+            self.hold_state = None;
+        }
 
         if self.bindings.move_left.contains(&code) {
             self.left_state = None;
@@ -235,6 +308,15 @@ impl InputHandler {
                 self.down_state = None;
             }
         }
+        if !self.releases_reported {
+            let timed_out = |state: &ButtonState| now.duration_since(state.last_seen) > KEY_TIMEOUT;
+            if self.rotate_state.as_ref().is_some_and(|(_, state)| timed_out(state)) {
+                self.rotate_state = None;
+            }
+            if self.hold_state.as_ref().is_some_and(timed_out) {
+                self.hold_state = None;
+            }
+        }
 
         // Copy DAS/ARR values to avoid borrow issues
         let das = self.das;
@@ -264,11 +346,28 @@ impl InputHandler {
         actions
     }
 
+    /// Rotate and hold buttons held down, for the game's IRS and IHS.
+    /// Without key releases a key only counts as held once it repeats, so a
+    /// quick tap before a drop doesn't carry over to the next piece.
+    pub fn held(&self) -> HeldInput {
+        let is_held = |state: &ButtonState| self.releases_reported || state.repeating;
+        HeldInput {
+            rotation: self
+                .rotate_state
+                .as_ref()
+                .filter(|(_, state)| is_held(state))
+                .map(|(direction, _)| *direction),
+            hold: self.hold_state.as_ref().is_some_and(is_held),
+        }
+    }
+
     /// Clear all held keys (useful for pause/resume)
     pub fn clear(&mut self) {
         self.left_state = None;
         self.right_state = None;
         self.down_state = None;
+        self.rotate_state = None;
+        self.hold_state = None;
     }
 }
 
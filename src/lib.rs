@@ -0,0 +1,44 @@
+//! TETRS engine
+//!
+//! The game logic behind the `tetrs` terminal client, usable on its own by
+//! bots, analyzers or other frontends. The engine is deterministic: a `Game`
+//! created with a seed and fed the same actions on the same ticks always
+//! plays out the same way.
+//!
+//! The terminal client is behind the default `tui` feature; depend on this
+//! crate with `default-features = false` to build only the engine.
+//!
+//! ```
+//! use tetrs::game::{Action, Game, TICK_RATE};
+//! use tetrs::mode::GameMode;
+//!
+//! let mut game = Game::with_seed(GameMode::Sprint(40), 42);
+//! // Run through the 3 second countdown
+//! for _ in 0..3 * TICK_RATE {
+//!     game.tick();
+//! }
+//! game.process_action(Action::HardDrop);
+//! assert_eq!(game.pieces_placed, 1);
+//! ```
+
+// Allow unreachable patterns due to #[non_exhaustive] enums for future compatibility
+#![allow(unreachable_patterns)]
+
+pub mod bag;
+pub mod board;
+pub mod custom;
+pub mod daily;
+pub mod game;
+pub mod garbage;
+pub mod gravity;
+pub mod mode;
+pub mod piece;
+pub mod practice;
+pub mod randomizer;
+pub mod replay;
+pub mod rotation;
+pub mod rules;
+pub mod score;
+pub mod settings;
+pub mod srs;
+pub mod tetromino;
@@ -6,18 +6,9 @@
 #![allow(unreachable_patterns)]
 
 mod audio;
-mod bag;
-mod board;
-mod game;
 mod input;
 mod menu;
-mod mode;
 mod multiplayer;
-mod piece;
-mod score;
-mod settings;
-mod srs;
-mod tetromino;
 mod ui;
 
 use audio::{AudioManager, BgmTrack, Sfx};
@@ -27,14 +18,17 @@ use crossterm::{
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use game::{Game, GameState};
+use tetrs::custom::CustomMode;
+use tetrs::daily;
+use tetrs::game::{Game, GameState};
 use input::InputHandler;
 use menu::{Menu, MenuAction, MenuScreen};
-use mode::GameMode;
+use tetrs::mode::GameMode;
 use multiplayer::{MultiplayerSession, NetEvent, Role};
+use tetrs::replay::{Replay, ReplayPlayer};
 use crossterm::event::MouseEvent;
 use ratatui::{backend::CrosstermBackend, layout::Rect, Terminal};
-use settings::Settings;
+use tetrs::settings::Settings;
 use std::{
     io::{self, stdout},
     time::{Duration, Instant},
@@ -53,6 +47,8 @@ enum AppState {
     Playing(Game, InputHandler),
     /// Versus mode with multiplayer session
     Versus(Game, InputHandler, MultiplayerSession),
+    /// Watching a saved replay
+    Replay(ReplayPlayer),
 }
 
 /// Get the tetrs temp directory, creating it if needed
@@ -194,12 +190,25 @@ fn main() -> io::Result<()> {
     match &result {
         Ok(Some(game)) => {
             println!("\n🦀 Thanks for playing TETRS! 🦀");
-            println!("Mode: {}", game.mode().name());
+            println!("Mode: {}", game.mode_name());
             println!("Final Score: {}", game.score.points);
             println!("Level: {} | Lines: {}", game.score.level, game.score.lines);
-            if game.mode() == GameMode::Sprint {
+            if game.mode().ranked_by_time() || game.mode_state.custom_goal().is_some_and(|goal| goal.ranked_by_time()) {
                 println!("Time: {}", game.mode_state.format_time());
             }
+            if let Some(master) = &game.mode_state.master {
+                println!("Grade: {} | Master Level: {}", master.grade(), master.level);
+            }
+            if game.mode() == GameMode::Zen {
+                let zen = &settings.zen_stats;
+                println!("Zen all time: {} lines over {} sessions", zen.lines, zen.sessions);
+            }
+            if let Some(day) = game.mode_state.day {
+                let date = daily::date_string(day);
+                if let Some(record) = settings.daily.get(&date) {
+                    println!("{}", record.share_line(&date));
+                }
+            }
         }
         Ok(None) => {
             println!("\n🦀 Thanks for playing TETRS! 🦀");
@@ -221,15 +230,22 @@ fn run_app(
     let mut last_countdown: Option<u8> = None;
     let mut last_action_text: Option<String> = None;
     let mut game_over_time: Option<Instant> = None;
+    // Personal best replay raced in Sprint and Ultra
+    let mut ghost: Option<ReplayPlayer> = None;
+    // Real time between frames drives the game and replay simulations
+    let mut last_frame = Instant::now();
 
     loop {
         // Render
         terminal.draw(|frame| match &state {
             AppState::Menu(menu) => ui::render_menu(frame, menu),
-            AppState::Playing(game, _) => ui::render_game(frame, game, settings),
+            AppState::Playing(game, _) => {
+                ui::render_game(frame, game, ghost.as_ref().map(|ghost| &ghost.game), settings);
+            }
             AppState::Versus(game, _, session) => {
                 ui::render_versus(frame, game, session, settings);
             }
+            AppState::Replay(player) => ui::render_replay(frame, player, settings),
         })?;
 
         // Handle input
@@ -330,20 +346,28 @@ fn run_app(
                                         }
                                         match action {
                                             MenuAction::StartGame(mode) => {
-                                                let game = Game::new(mode);
+                                                ghost = new_ghost(mode, settings);
+                                                let game = new_game(mode, settings, ghost.as_ref());
                                                 let input = InputHandler::from_settings(settings);
-                                                // Start background music
-                                                if let Some(audio) = audio {
-                                                    let track = match settings.audio.bgm_track.as_str() {
-                                                        "Korobeiniki (Fast)" => BgmTrack::KorobeinikiFast,
-                                                        "Kalinka" => BgmTrack::Kalinka,
-                                                        "Ievan Polkka" => BgmTrack::IevanPolkka,
-                                                        _ => BgmTrack::Korobeiniki,
-                                                    };
-                                                    audio.play_bgm(track);
-                                                }
+                                                play_game_bgm(audio, settings);
                                                 state = AppState::Playing(game, input);
                                             }
+                                            MenuAction::StartCustom(name) => {
+                                                if let Some(custom) = CustomMode::find(&name) {
+                                                    ghost = None;
+                                                    let game = new_custom_game(&custom, settings);
+                                                    let input = InputHandler::from_settings(settings);
+                                                    play_game_bgm(audio, settings);
+                                                    state = AppState::Playing(game, input);
+                                                }
+                                            }
+                                            MenuAction::ExportDaily => export_daily(menu, settings),
+                                            MenuAction::SaveCustomMode => {
+                                                match menu.custom_mode().save() {
+                                                    Ok(()) => menu.go_to(MenuScreen::CustomModes, settings),
+                                                    Err(e) => menu.show_message(&e),
+                                                }
+                                            }
                                             MenuAction::GoToScreen(screen) => {
                                                 menu.go_to(screen, settings);
                                             }
@@ -359,6 +383,11 @@ fn run_app(
                                             MenuAction::SaveSettings => {
                                                 let _ = settings.save();
                                             }
+                                            MenuAction::WatchReplay(path) => {
+                                                if let Some(player) = open_replay(&path) {
+                                                    state = AppState::Replay(player);
+                                                }
+                                            }
                                             MenuAction::HostGame => {
                                                 // Generate a random seed for this game
                                                 let seed = rand::random::<u64>();
@@ -386,7 +415,7 @@ fn run_app(
                                                         };
 
                                                         // Create game with our seed
-                                                        let game = Game::with_seed(GameMode::Versus, seed);
+                                                        let game = new_versus_game(seed, settings);
                                                         let input = InputHandler::from_settings(settings);
                                                         state = AppState::Versus(game, input, session);
                                                     }
@@ -419,8 +448,8 @@ fn run_app(
                                                             session.set_channels(cmd_tx, event_rx);
                                                             session.state = multiplayer::ConnectionState::Connecting;
 
-                                                            // Transition to versus game
-                                                            let game = Game::new(GameMode::Versus);
+                                                            // Transition to versus game (reseeded once the host's seed arrives)
+                                                            let game = new_versus_game(rand::random(), settings);
                                                             let input = InputHandler::from_settings(settings);
                                                             state = AppState::Versus(game, input, session);
                                                         }
@@ -463,8 +492,9 @@ fn run_app(
                                     // Track when game ended
                                     if game_over_time.is_none() {
                                         game_over_time = Some(Instant::now());
-                                        // Save high score
+                                        // Save high score and replay
                                         save_high_score(game, settings);
+                                        save_replay(game, settings);
                                         // Stop BGM
                                         if let Some(audio) = audio {
                                             audio.stop_bgm();
@@ -511,6 +541,31 @@ fn run_app(
                                 session.send_disconnect();
                             }
                         }
+                        AppState::Replay(player) => {
+                            match key.code {
+                                KeyCode::Char(' ') => player.toggle_pause(),
+                                KeyCode::Right => player.step_piece_forward(),
+                                KeyCode::Left => player.step_piece_back(),
+                                KeyCode::Up => player.faster(),
+                                KeyCode::Down => player.slower(),
+                                KeyCode::Char(c) if c.is_ascii_digit() && player.seek_input.len() < 6 => {
+                                    player.seek_input.push(c);
+                                }
+                                KeyCode::Backspace => {
+                                    player.seek_input.pop();
+                                }
+                                KeyCode::Enter => {
+                                    if let Ok(piece) = player.seek_input.parse::<u32>() {
+                                        player.seek_to_piece(piece);
+                                    }
+                                    player.seek_input.clear();
+                                }
+                                KeyCode::Esc | KeyCode::Char('q') => {
+                                    state = AppState::Menu(Menu::replays_menu());
+                                }
+                                _ => {}
+                            }
+                        }
                     }
 
                     // Handle state transitions outside the match to avoid borrow issues
@@ -520,12 +575,17 @@ fn run_app(
                                 if let Some(audio) = audio {
                                     audio.stop_bgm();
                                 }
+                                // Keep games abandoned mid-play
+                                if game_over_time.is_none() {
+                                    save_replay(game, settings);
+                                }
                                 game_over_time = None;
                                 true
                             } else if matches!(session.state, multiplayer::ConnectionState::GameOver { .. }) {
                                 // Track when game ended
                                 if game_over_time.is_none() {
                                     game_over_time = Some(Instant::now());
+                                    save_replay(game, settings);
                                     if let Some(audio) = audio {
                                         audio.stop_bgm();
                                     }
@@ -535,7 +595,7 @@ fn run_app(
                                 if game_over_time.map_or(false, |t| t.elapsed() >= GAME_OVER_INPUT_DELAY) {
                                     // Reset to lobby for rematch instead of menu
                                     session.reset_for_rematch();
-                                    *game = Game::with_seed(GameMode::Versus, session.game_seed);
+                                    *game = new_versus_game(session.game_seed, settings);
                                     game_over_time = None;
                                 }
                                 false // Don't return to menu
@@ -561,10 +621,26 @@ fn run_app(
                         if let Some(action) = handle_menu_mouse(menu, mouse, area, settings) {
                             match action {
                                 MenuAction::StartGame(mode) => {
-                                    let game = Game::new(mode);
+                                    ghost = new_ghost(mode, settings);
+                                    let game = new_game(mode, settings, ghost.as_ref());
                                     let input = InputHandler::from_settings(settings);
                                     state = AppState::Playing(game, input);
                                 }
+                                MenuAction::StartCustom(name) => {
+                                    if let Some(custom) = CustomMode::find(&name) {
+                                        ghost = None;
+                                        let game = new_custom_game(&custom, settings);
+                                        let input = InputHandler::from_settings(settings);
+                                        state = AppState::Playing(game, input);
+                                    }
+                                }
+                                MenuAction::ExportDaily => export_daily(menu, settings),
+                                MenuAction::SaveCustomMode => {
+                                    match menu.custom_mode().save() {
+                                        Ok(()) => menu.go_to(MenuScreen::CustomModes, settings),
+                                        Err(e) => menu.show_message(&e),
+                                    }
+                                }
                                 MenuAction::GoToScreen(screen) => {
                                     menu.go_to(screen, settings);
                                 }
@@ -577,6 +653,11 @@ fn run_app(
                                 MenuAction::SaveSettings => {
                                     let _ = settings.save();
                                 }
+                                MenuAction::WatchReplay(path) => {
+                                    if let Some(player) = open_replay(&path) {
+                                        state = AppState::Replay(player);
+                                    }
+                                }
                                 MenuAction::HostGame | MenuAction::JoinGame => {
                                     // TODO: Networking
                                 }
@@ -589,6 +670,10 @@ fn run_app(
             }
         }
 
+        let now = Instant::now();
+        let frame_dt = now.duration_since(last_frame);
+        last_frame = now;
+
         // Update game state
         match &mut state {
             AppState::Playing(game, input) => {
@@ -597,6 +682,7 @@ fn run_app(
                 for action in held_actions {
                     game.process_action(action);
                 }
+                game.set_held(input.held());
 
                 // Check countdown for SFX
                 if let GameState::Countdown(count) = game.state {
@@ -615,7 +701,10 @@ fn run_app(
                 }
 
                 // Update game logic
-                game.update();
+                game.advance(frame_dt);
+                if let (Some(ghost), Some(frames)) = (&mut ghost, game.play_frames()) {
+                    ghost.catch_up(frames);
+                }
 
                 // Play SFX for line clears
                 if game.last_action != last_action_text {
@@ -670,7 +759,7 @@ fn run_app(
                         NetEvent::SeedReceived { seed } => {
                             // Guest receives seed from host - recreate game with same seed
                             session.game_seed = seed;
-                            *game = Game::with_seed(GameMode::Versus, seed);
+                            *game = new_versus_game(seed, settings);
                         }
                         NetEvent::OpponentReady => {
                             session.set_opponent_ready();
@@ -678,8 +767,8 @@ fn run_app(
                         NetEvent::Countdown { value } => {
                             session.receive_countdown(value);
                         }
-                        NetEvent::BoardUpdate { cells, score, lines, level } => {
-                            session.opponent.update_from_message(&cells, score, lines, level);
+                        NetEvent::BoardUpdate { cells, width, score, lines, level } => {
+                            session.opponent.update_from_message(&cells, width, score, lines, level);
                         }
                         NetEvent::GarbageReceived { lines } => {
                             session.pending_garbage += lines;
@@ -739,33 +828,23 @@ fn run_app(
                         for action in held_actions {
                             game.process_action(action);
                         }
+                        game.set_held(input.held());
 
                         // Update game logic
-                        game.update();
+                        game.advance(frame_dt);
 
                         // Check if piece was locked - send board state and garbage
                         if game.piece_just_locked {
                             session.send_board_state(game);
 
-                            // Calculate and send garbage if we cleared lines
-                            if let Some(ref clear_info) = game.last_clear_info {
-                                let garbage = multiplayer::calculate_garbage(
-                                    clear_info.lines as u32,
-                                    clear_info.is_tspin,
-                                    clear_info.combo.max(0) as u32,
-                                    clear_info.back_to_back,
-                                );
-                                if garbage > 0 {
-                                    session.send_garbage(garbage);
-                                }
-                            }
+                            // Send whatever attack is left after cancelling incoming garbage
+                            session.send_garbage(game.take_outgoing_garbage());
 
                             game.piece_just_locked = false;
                         }
 
-                        // Apply pending garbage (TODO: add garbage lines to board)
-                        let _garbage = session.take_pending_garbage();
-                        // if garbage > 0 { game.add_garbage_lines(garbage); }
+                        // Queue received garbage; it enters the board on a later lock
+                        game.queue_garbage(session.take_pending_garbage());
 
                         // Check for game over
                         if game.state == GameState::GameOver {
@@ -812,26 +891,160 @@ fn run_app(
                     }
                 }
             }
+            AppState::Replay(player) => player.update(frame_dt),
             AppState::Menu(_) => {}
         }
     }
 }
 
+/// Load a replay for watching, logging why it could not be opened
+fn open_replay(path: &std::path::Path) -> Option<ReplayPlayer> {
+    match Replay::load(path) {
+        Ok(replay) => Some(ReplayPlayer::new(replay)),
+        Err(e) => {
+            tracing::warn!("Could not open replay {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Create a single player game with the rules from our settings. A race
+/// against a ghost is dealt the ghost's pieces under the ghost's rules, and
+/// the Daily challenge ignores settings overrides so everyone plays the same
+/// game.
+fn new_game(mode: GameMode, settings: &Settings, ghost: Option<&ReplayPlayer>) -> Game {
+    if mode == GameMode::Daily {
+        return Game::with_daily(daily::today());
+    }
+    match ghost {
+        Some(ghost) => Game::with_rules(mode, ghost.replay.seed, ghost.replay.rules.clone()),
+        None => Game::with_rules(mode, rand::random(), settings.gameplay.rules_for(mode)),
+    }
+}
+
+/// Export today's daily challenge result and show where it was saved
+fn export_daily(menu: &mut Menu, settings: &Settings) {
+    let date = daily::date_string(daily::today());
+    let record = settings.daily.get(&date).cloned().unwrap_or_default();
+    match daily::export(&record.share_line(&date)) {
+        Ok(path) => *menu = Menu::daily_menu(settings, Some(&path)),
+        Err(e) => tracing::warn!("Could not export daily result: {}", e),
+    }
+}
+
+/// Load the personal best to race as a ghost, if ghost racing is on and
+/// the mode has one saved
+fn new_ghost(mode: GameMode, settings: &Settings) -> Option<ReplayPlayer> {
+    if !settings.gameplay.ghost_race {
+        return None;
+    }
+    Replay::load_pb(mode).map(ReplayPlayer::new)
+}
+
+/// Create a game of a custom mode with a fresh seed and our rule overrides
+fn new_custom_game(custom: &CustomMode, settings: &Settings) -> Game {
+    Game::with_custom(custom, rand::random(), settings.gameplay.rules_for_custom(custom))
+}
+
+/// Start the background music picked in audio settings
+fn play_game_bgm(audio: &mut Option<AudioManager>, settings: &Settings) {
+    if let Some(audio) = audio {
+        let track = match settings.audio.bgm_track.as_str() {
+            "Korobeiniki (Fast)" => BgmTrack::KorobeinikiFast,
+            "Kalinka" => BgmTrack::Kalinka,
+            "Ievan Polkka" => BgmTrack::IevanPolkka,
+            _ => BgmTrack::Korobeiniki,
+        };
+        audio.play_bgm(track);
+    }
+}
+
+/// Create a Versus game with the shared seed and our garbage settings
+fn new_versus_game(seed: u64, settings: &Settings) -> Game {
+    let rules = settings.gameplay.rules_for(GameMode::Versus);
+    let mut game = Game::with_rules(GameMode::Versus, seed, rules);
+    game.garbage_queue
+        .set_delay(Duration::from_millis(settings.gameplay.garbage_delay_ms));
+    // The session runs the countdown before the game starts advancing
+    game.skip_countdown();
+    game
+}
+
+/// Save a replay of the game to the config dir (skipped if play never started)
+fn save_replay(game: &Game, settings: &Settings) {
+    if game.mode_state.start_time.is_none() {
+        return;
+    }
+    let replay = Replay::from_game(game, settings);
+    match replay.save() {
+        Ok(path) => tracing::info!("Saved replay to {}", path.display()),
+        Err(e) => tracing::warn!("Could not save replay: {}", e),
+    }
+    if is_personal_best(game, settings) {
+        match replay.save_pb() {
+            Ok(path) => tracing::info!("Saved personal best replay to {}", path.display()),
+            Err(e) => tracing::warn!("Could not save personal best replay: {}", e),
+        }
+    }
+}
+
+/// Whether the game set a new personal best for ghost racing (checked after
+/// its high score is saved)
+fn is_personal_best(game: &Game, settings: &Settings) -> bool {
+    match game.mode() {
+        GameMode::Sprint(distance) if game.state == GameState::Victory => {
+            settings.best_sprint(distance) == Some(game.mode_state.elapsed.as_millis() as u64)
+        }
+        GameMode::Ultra => settings.best_ultra() == Some(game.score.points),
+        _ => false,
+    }
+}
+
 /// Save high score based on game mode
 fn save_high_score(game: &Game, settings: &mut Settings) {
     match game.mode() {
         GameMode::Marathon => {
             settings.add_marathon_score(game.score.points, game.score.lines, game.score.level);
         }
-        GameMode::Sprint => {
-            if game.state == GameState::Victory {
-                let time_ms = game.mode_state.elapsed.as_millis() as u64;
-                settings.add_sprint_score(time_ms, game.score.lines, game.score.level);
-            }
+        GameMode::Sprint(distance) if game.state == GameState::Victory => {
+            let time_ms = game.mode_state.elapsed.as_millis() as u64;
+            let splits = game.mode_state.splits.iter().map(|split| split.as_millis() as u64).collect();
+            settings.add_sprint_score(time_ms, distance, game.score.level, splits);
         }
         GameMode::Ultra => {
             settings.add_ultra_score(game.score.points, game.score.lines, game.score.level);
         }
+        GameMode::Custom => {
+            if let Some(custom) = &game.mode_state.custom
+                && (game.state == GameState::Victory || !custom.goal.ranked_by_time())
+            {
+                let time_ms = game.mode_state.elapsed.as_millis() as u64;
+                settings.add_custom_score(custom, game.score.points, time_ms, game.score.lines, game.score.level);
+            }
+        }
+        GameMode::Blitz => {
+            settings.add_blitz_score(game.score.points, game.score.lines, game.score.level);
+        }
+        GameMode::Daily => {
+            // The day the game started on, even if it ended past midnight
+            if let Some(day) = game.mode_state.day {
+                let time_ms = game.mode_state.elapsed.as_millis() as u64;
+                let finished = (game.state == GameState::Victory).then_some(time_ms);
+                settings.add_daily_attempt(&daily::date_string(day), finished);
+            }
+        }
+        GameMode::Zen => {
+            let time_ms = game.mode_state.elapsed.as_millis() as u64;
+            settings.add_zen_session(time_ms, game.pieces_placed, game.score.lines, game.score.points);
+        }
+        GameMode::Survival => {
+            let time_ms = game.mode_state.elapsed.as_millis() as u64;
+            settings.add_survival_score(time_ms, game.score.lines, game.score.level);
+        }
+        GameMode::Cheese(garbage_lines) if game.state == GameState::Victory => {
+            let time_ms = game.mode_state.elapsed.as_millis() as u64;
+            settings.add_cheese_score(time_ms, garbage_lines, game.score.level);
+        }
         GameMode::Versus => {
             // Versus mode doesn't save high scores (multiplayer results)
         }
@@ -842,18 +1055,10 @@ fn save_high_score(game: &Game, settings: &mut Settings) {
 /// Handle mouse events in the menu
 fn handle_menu_mouse(menu: &mut Menu, mouse: MouseEvent, size: Rect, settings: &mut Settings) -> Option<MenuAction> {
     // Menu layout constants - dynamic based on screen type
-    let (menu_width, menu_height) = match menu.screen {
-        MenuScreen::Main | MenuScreen::ModeSelect => (44u16, 18u16),
-        MenuScreen::Settings => (44u16, 16u16),
-        MenuScreen::SettingsKeys => (50u16, 24u16),
-        MenuScreen::SettingsVisual | MenuScreen::SettingsGameplay | MenuScreen::SettingsAudio => (50u16, 14u16),
-        MenuScreen::Multiplayer => (44u16, 14u16),
-        MenuScreen::HostGame | MenuScreen::JoinGame => (60u16, 14u16),
-        _ => (44u16, 16u16),
-    };
-
-    let show_big_title = matches!(menu.screen, MenuScreen::Main | MenuScreen::ModeSelect);
-    let title_height: u16 = if show_big_title { 6 } else { 3 };
+    let (menu_width, menu_height) = ui::menu_size(&menu.screen);
+    let menu_height = menu_height.min(size.height);
+    let title_height = ui::menu_title_height(&menu.screen);
+    let (first, visible) = ui::menu_scroll(menu, size);
 
     // Calculate menu area (centered)
     let menu_x = size.x + size.width.saturating_sub(menu_width) / 2;
@@ -873,9 +1078,9 @@ fn handle_menu_mouse(menu: &mut Menu, mouse: MouseEvent, size: Rect, settings: &
             if x >= inner_x && x < inner_x + inner_width && y > inner_y {
                 // Each menu item takes 2 rows (item + spacing), starting with 1 row of spacing
                 let relative_y = y - inner_y - 1; // -1 for initial spacing
-                let item_index = (relative_y / 2) as usize;
+                let item_index = first + (relative_y / 2) as usize;
 
-                if item_index < menu.items.len() {
+                if item_index < first + visible {
                     menu.selected = item_index;
 
                     // Check item type and handle accordingly
@@ -917,9 +1122,9 @@ fn handle_menu_mouse(menu: &mut Menu, mouse: MouseEvent, size: Rect, settings: &
             // Highlight on hover
             if x >= inner_x && x < inner_x + inner_width && y > inner_y {
                 let relative_y = y - inner_y - 1;
-                let item_index = (relative_y / 2) as usize;
+                let item_index = first + (relative_y / 2) as usize;
 
-                if item_index < menu.items.len() {
+                if item_index < first + visible {
                     menu.selected = item_index;
                 }
             }
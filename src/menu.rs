@@ -1,7 +1,18 @@
 //! Main menu system with settings configuration
 
-use crate::mode::GameMode;
-use crate::settings::Settings;
+use tetrs::custom::{CustomMode, Goal, BOARD_HEIGHTS, BOARD_WIDTHS, MAX_PREVIEW, STARTING_LEVELS};
+use tetrs::daily::{self, DailyRecord};
+use tetrs::gravity::{GravityCurve, TWENTY_G};
+use tetrs::mode::{CheeseState, GameMode};
+use tetrs::randomizer::RandomizerKind;
+use tetrs::replay::Replay;
+use tetrs::rotation::RotationSystemKind;
+use tetrs::rules::LockReset;
+use tetrs::settings::Settings;
+use std::path::{Path, PathBuf};
+
+/// Option label for settings that defer to the mode's rules
+const MODE_DEFAULT: &str = "Mode Default";
 
 /// Menu screens
 #[non_exhaustive]
@@ -9,6 +20,11 @@ use crate::settings::Settings;
 pub enum MenuScreen {
     Main,
     ModeSelect,
+    Sprint,
+    CheeseRace,
+    Daily,
+    CustomModes,
+    CustomBuilder,
     Settings,
     SettingsKeys,
     SettingsVisual,
@@ -17,6 +33,7 @@ pub enum MenuScreen {
     Multiplayer,
     HostGame,
     JoinGame,
+    Replays,
 }
 
 /// Menu state
@@ -66,6 +83,14 @@ pub enum MenuAction {
     HostGame,
     /// Join with the entered ticket
     JoinGame,
+    /// Watch a saved replay
+    WatchReplay(PathBuf),
+    /// Play the saved custom mode with this name
+    StartCustom(String),
+    /// Save the custom mode being built
+    SaveCustomMode,
+    /// Write today's daily challenge result to a file to share
+    ExportDaily,
 }
 
 /// Setting keys for identifying which setting to modify
@@ -75,6 +100,28 @@ pub enum SettingKey {
     BlockStyle,
     DasMs,
     ArrMs,
+    GarbageDelayMs,
+    RotationSystem,
+    LockDelayMs,
+    LockReset,
+    LockResetLimit,
+    AreMs,
+    LineClearDelayMs,
+    Irs,
+    Ihs,
+    CheeseMessiness,
+    ZenLevel,
+    GhostRace,
+    PracticeLevel,
+    CustomGoal,
+    CustomLevel,
+    CustomGravity,
+    CustomRandomizer,
+    CustomPreview,
+    CustomHold,
+    CustomGhost,
+    CustomWidth,
+    CustomHeight,
     BgmVolume,
     SfxVolume,
     BgmTrack,
@@ -95,6 +142,10 @@ impl Menu {
                     label: "Play".to_string(),
                     item_type: MenuItemType::Button(MenuAction::GoToScreen(MenuScreen::ModeSelect)),
                 },
+                MenuItem {
+                    label: "Replays".to_string(),
+                    item_type: MenuItemType::Button(MenuAction::GoToScreen(MenuScreen::Replays)),
+                },
                 MenuItem {
                     label: "Settings".to_string(),
                     item_type: MenuItemType::Button(MenuAction::GoToScreen(MenuScreen::Settings)),
@@ -118,13 +169,45 @@ impl Menu {
                     item_type: MenuItemType::Button(MenuAction::StartGame(GameMode::Marathon)),
                 },
                 MenuItem {
-                    label: "Sprint (40 Lines)".to_string(),
-                    item_type: MenuItemType::Button(MenuAction::StartGame(GameMode::Sprint)),
+                    label: "Sprint".to_string(),
+                    item_type: MenuItemType::Button(MenuAction::GoToScreen(MenuScreen::Sprint)),
                 },
                 MenuItem {
                     label: "Ultra (3 Minutes)".to_string(),
                     item_type: MenuItemType::Button(MenuAction::StartGame(GameMode::Ultra)),
                 },
+                MenuItem {
+                    label: "Blitz (2 Minutes)".to_string(),
+                    item_type: MenuItemType::Button(MenuAction::StartGame(GameMode::Blitz)),
+                },
+                MenuItem {
+                    label: "Master (TGM)".to_string(),
+                    item_type: MenuItemType::Button(MenuAction::StartGame(GameMode::Master)),
+                },
+                MenuItem {
+                    label: "Cheese Race".to_string(),
+                    item_type: MenuItemType::Button(MenuAction::GoToScreen(MenuScreen::CheeseRace)),
+                },
+                MenuItem {
+                    label: "Survival".to_string(),
+                    item_type: MenuItemType::Button(MenuAction::StartGame(GameMode::Survival)),
+                },
+                MenuItem {
+                    label: "Zen".to_string(),
+                    item_type: MenuItemType::Button(MenuAction::StartGame(GameMode::Zen)),
+                },
+                MenuItem {
+                    label: "Practice".to_string(),
+                    item_type: MenuItemType::Button(MenuAction::StartGame(GameMode::Practice)),
+                },
+                MenuItem {
+                    label: "Daily Challenge".to_string(),
+                    item_type: MenuItemType::Button(MenuAction::GoToScreen(MenuScreen::Daily)),
+                },
+                MenuItem {
+                    label: "Custom".to_string(),
+                    item_type: MenuItemType::Button(MenuAction::GoToScreen(MenuScreen::CustomModes)),
+                },
                 MenuItem {
                     label: "Versus (Online)".to_string(),
                     item_type: MenuItemType::Button(MenuAction::GoToScreen(MenuScreen::Multiplayer)),
@@ -137,6 +220,318 @@ impl Menu {
         }
     }
 
+    pub fn sprint_menu() -> Self {
+        let mut items: Vec<MenuItem> = GameMode::SPRINT_DISTANCES
+            .iter()
+            .map(|&distance| MenuItem {
+                label: format!("{} Lines", distance),
+                item_type: MenuItemType::Button(MenuAction::StartGame(GameMode::Sprint(distance))),
+            })
+            .collect();
+        items.push(MenuItem {
+            label: "Back".to_string(),
+            item_type: MenuItemType::Button(MenuAction::Back),
+        });
+
+        Self {
+            screen: MenuScreen::Sprint,
+            selected: 0,
+            rebinding: None,
+            items,
+        }
+    }
+
+    pub fn cheese_race_menu() -> Self {
+        let mut items: Vec<MenuItem> = CheeseState::TARGETS
+            .iter()
+            .map(|&target| MenuItem {
+                label: format!("{} Lines", target),
+                item_type: MenuItemType::Button(MenuAction::StartGame(GameMode::Cheese(target))),
+            })
+            .collect();
+        items.push(MenuItem {
+            label: "Back".to_string(),
+            item_type: MenuItemType::Button(MenuAction::Back),
+        });
+
+        Self {
+            screen: MenuScreen::CheeseRace,
+            selected: 0,
+            rebinding: None,
+            items,
+        }
+    }
+
+    /// Today's challenge, its share line and the last few days' results
+    pub fn daily_menu(settings: &Settings, exported: Option<&Path>) -> Self {
+        let date = daily::date_string(daily::today());
+        let today = settings.daily.get(&date);
+
+        let mut items = vec![
+            MenuItem {
+                label: format!("Play {}", date),
+                item_type: MenuItemType::Button(MenuAction::StartGame(GameMode::Daily)),
+            },
+            MenuItem {
+                label: "Today".to_string(),
+                item_type: MenuItemType::Label {
+                    text: today.map_or("No attempts yet".to_string(), DailyRecord::summary),
+                },
+            },
+        ];
+        items.extend(
+            settings
+                .daily
+                .iter()
+                .rev()
+                .filter(|(day, _)| **day != date)
+                .take(5)
+                .map(|(day, record)| MenuItem {
+                    label: day.clone(),
+                    item_type: MenuItemType::Label { text: record.summary() },
+                }),
+        );
+        if today.is_some() {
+            items.push(MenuItem {
+                label: "Export Result".to_string(),
+                item_type: MenuItemType::Button(MenuAction::ExportDaily),
+            });
+        }
+        if let Some(path) = exported {
+            items.push(MenuItem {
+                label: "Saved to".to_string(),
+                item_type: MenuItemType::Label { text: path.display().to_string() },
+            });
+        }
+        items.push(MenuItem {
+            label: "Back".to_string(),
+            item_type: MenuItemType::Button(MenuAction::Back),
+        });
+
+        Self {
+            screen: MenuScreen::Daily,
+            selected: 0,
+            rebinding: None,
+            items,
+        }
+    }
+
+    pub fn custom_modes_menu() -> Self {
+        let mut items: Vec<MenuItem> = match CustomMode::load_all() {
+            Ok(modes) => modes
+                .into_iter()
+                .map(|custom| MenuItem {
+                    label: format!("{} ({})", custom.name, custom.goal.name()),
+                    item_type: MenuItemType::Button(MenuAction::StartCustom(custom.name)),
+                })
+                .collect(),
+            Err(e) => vec![MenuItem {
+                label: e.lines().next().unwrap_or_default().to_string(),
+                item_type: MenuItemType::Label { text: String::new() },
+            }],
+        };
+        items.push(MenuItem {
+            label: "New Custom Mode".to_string(),
+            item_type: MenuItemType::Button(MenuAction::GoToScreen(MenuScreen::CustomBuilder)),
+        });
+        items.push(MenuItem {
+            label: "Back".to_string(),
+            item_type: MenuItemType::Button(MenuAction::Back),
+        });
+
+        Self {
+            screen: MenuScreen::CustomModes,
+            selected: 0,
+            rebinding: None,
+            items,
+        }
+    }
+
+    pub fn custom_builder() -> Self {
+        let custom = CustomMode::default();
+        let goals = Goal::presets();
+        let current_goal = goals.iter().position(|&goal| goal == custom.goal).unwrap_or(0);
+        let randomizers = RandomizerKind::all().iter().map(|kind| kind.name().to_string()).collect();
+        let current_randomizer = RandomizerKind::all()
+            .iter()
+            .position(|&kind| kind == custom.randomizer)
+            .unwrap_or(0);
+
+        Self {
+            screen: MenuScreen::CustomBuilder,
+            selected: 0,
+            rebinding: None,
+            items: vec![
+                MenuItem {
+                    label: "Name".to_string(),
+                    item_type: MenuItemType::TextInput {
+                        value: String::new(),
+                        placeholder: "Names the leaderboard".to_string(),
+                    },
+                },
+                MenuItem {
+                    label: "Goal".to_string(),
+                    item_type: MenuItemType::Cycle {
+                        key: SettingKey::CustomGoal,
+                        options: goals.iter().map(Goal::name).collect(),
+                        current: current_goal,
+                    },
+                },
+                MenuItem {
+                    label: "Starting Level".to_string(),
+                    item_type: MenuItemType::Number {
+                        key: SettingKey::CustomLevel,
+                        value: custom.starting_level as u64,
+                        min: *STARTING_LEVELS.start() as u64,
+                        max: *STARTING_LEVELS.end() as u64,
+                        step: 1,
+                    },
+                },
+                MenuItem {
+                    label: "Gravity".to_string(),
+                    item_type: MenuItemType::Cycle {
+                        key: SettingKey::CustomGravity,
+                        options: GRAVITY_OPTIONS.iter().map(|option| option.to_string()).collect(),
+                        current: 0,
+                    },
+                },
+                MenuItem {
+                    label: "Randomizer".to_string(),
+                    item_type: MenuItemType::Cycle {
+                        key: SettingKey::CustomRandomizer,
+                        options: randomizers,
+                        current: current_randomizer,
+                    },
+                },
+                MenuItem {
+                    label: "Preview".to_string(),
+                    item_type: MenuItemType::Number {
+                        key: SettingKey::CustomPreview,
+                        value: custom.preview_count as u64,
+                        min: 0,
+                        max: MAX_PREVIEW as u64,
+                        step: 1,
+                    },
+                },
+                MenuItem {
+                    label: "Hold".to_string(),
+                    item_type: MenuItemType::Toggle {
+                        key: SettingKey::CustomHold,
+                        value: custom.hold,
+                    },
+                },
+                MenuItem {
+                    label: "Ghost".to_string(),
+                    item_type: MenuItemType::Toggle {
+                        key: SettingKey::CustomGhost,
+                        value: custom.ghost,
+                    },
+                },
+                MenuItem {
+                    label: "Board Width".to_string(),
+                    item_type: MenuItemType::Number {
+                        key: SettingKey::CustomWidth,
+                        value: custom.board_width as u64,
+                        min: *BOARD_WIDTHS.start() as u64,
+                        max: *BOARD_WIDTHS.end() as u64,
+                        step: 1,
+                    },
+                },
+                MenuItem {
+                    label: "Board Height".to_string(),
+                    item_type: MenuItemType::Number {
+                        key: SettingKey::CustomHeight,
+                        value: custom.board_height as u64,
+                        min: *BOARD_HEIGHTS.start() as u64,
+                        max: *BOARD_HEIGHTS.end() as u64,
+                        step: 1,
+                    },
+                },
+                MenuItem {
+                    label: "Save".to_string(),
+                    item_type: MenuItemType::Button(MenuAction::SaveCustomMode),
+                },
+                MenuItem {
+                    label: "Back".to_string(),
+                    item_type: MenuItemType::Button(MenuAction::Back),
+                },
+            ],
+        }
+    }
+
+    /// Custom mode described by the builder's current values
+    pub fn custom_mode(&self) -> CustomMode {
+        let mut custom = CustomMode::default();
+        for item in &self.items {
+            match &item.item_type {
+                MenuItemType::TextInput { value, .. } => custom.name = value.trim().to_string(),
+                MenuItemType::Cycle { key, options, current } => {
+                    let option = options[*current].as_str();
+                    match key {
+                        SettingKey::CustomGoal => {
+                            if let Some(goal) = Goal::presets().into_iter().find(|goal| goal.name() == option) {
+                                custom.goal = goal;
+                            }
+                        }
+                        SettingKey::CustomGravity => custom.gravity = gravity_option(option),
+                        SettingKey::CustomRandomizer => {
+                            custom.randomizer = RandomizerKind::from_name(option).unwrap_or_default();
+                        }
+                        _ => {}
+                    }
+                }
+                MenuItemType::Number { key, value, .. } => match key {
+                    SettingKey::CustomLevel => custom.starting_level = *value as u32,
+                    SettingKey::CustomPreview => custom.preview_count = *value as usize,
+                    SettingKey::CustomWidth => custom.board_width = *value as usize,
+                    SettingKey::CustomHeight => custom.board_height = *value as usize,
+                    _ => {}
+                },
+                MenuItemType::Toggle { key, value } => match key {
+                    SettingKey::CustomHold => custom.hold = *value,
+                    SettingKey::CustomGhost => custom.ghost = *value,
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+        custom
+    }
+
+    pub fn replays_menu() -> Self {
+        let now_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        // Labels come from file names; a replay is only read when it is watched
+        let mut items: Vec<MenuItem> = Replay::list()
+            .into_iter()
+            .map(|file| MenuItem {
+                label: format!("{} - {}", file.mode.replace('-', " "), saved_ago(file.saved_ms, now_ms)),
+                item_type: MenuItemType::Button(MenuAction::WatchReplay(file.path)),
+            })
+            .collect();
+
+        if items.is_empty() {
+            items.push(MenuItem {
+                label: "No replays yet".to_string(),
+                item_type: MenuItemType::Label { text: String::new() },
+            });
+        }
+
+        items.push(MenuItem {
+            label: "Back".to_string(),
+            item_type: MenuItemType::Button(MenuAction::Back),
+        });
+
+        Self {
+            screen: MenuScreen::Replays,
+            selected: 0,
+            rebinding: None,
+            items,
+        }
+    }
+
     pub fn multiplayer_menu() -> Self {
         Self {
             screen: MenuScreen::Multiplayer,
@@ -333,6 +728,13 @@ impl Menu {
                         keys: settings.keys.rotate_ccw.clone(),
                     },
                 },
+                MenuItem {
+                    label: "Rotate 180".to_string(),
+                    item_type: MenuItemType::KeyBind {
+                        action: "rotate_180".to_string(),
+                        keys: settings.keys.rotate_180.clone(),
+                    },
+                },
                 MenuItem {
                     label: "Hold".to_string(),
                     item_type: MenuItemType::KeyBind {
@@ -347,6 +749,34 @@ impl Menu {
                         keys: settings.keys.pause.clone(),
                     },
                 },
+                MenuItem {
+                    label: "Undo (Practice)".to_string(),
+                    item_type: MenuItemType::KeyBind {
+                        action: "undo".to_string(),
+                        keys: settings.keys.undo.clone(),
+                    },
+                },
+                MenuItem {
+                    label: "Redo (Practice)".to_string(),
+                    item_type: MenuItemType::KeyBind {
+                        action: "redo".to_string(),
+                        keys: settings.keys.redo.clone(),
+                    },
+                },
+                MenuItem {
+                    label: "Reset (Practice)".to_string(),
+                    item_type: MenuItemType::KeyBind {
+                        action: "reset".to_string(),
+                        keys: settings.keys.reset.clone(),
+                    },
+                },
+                MenuItem {
+                    label: "Next Piece (Practice)".to_string(),
+                    item_type: MenuItemType::KeyBind {
+                        action: "next_piece".to_string(),
+                        keys: settings.keys.next_piece.clone(),
+                    },
+                },
                 MenuItem {
                     label: "Back".to_string(),
                     item_type: MenuItemType::Button(MenuAction::Back),
@@ -390,6 +820,31 @@ impl Menu {
     }
 
     pub fn settings_gameplay(settings: &Settings) -> Self {
+        // First option leaves the choice to each mode
+        let mut rotation_systems = vec![MODE_DEFAULT.to_string()];
+        rotation_systems.extend(RotationSystemKind::all().iter().map(|kind| kind.name().to_string()));
+        let current_rotation = settings
+            .gameplay
+            .rotation_system
+            .and_then(|kind| rotation_systems.iter().position(|name| name == kind.name()))
+            .unwrap_or(0);
+
+        let mut lock_resets = vec![MODE_DEFAULT.to_string()];
+        lock_resets.extend(LockReset::all().iter().map(|reset| reset.name().to_string()));
+        let current_lock_reset = settings
+            .gameplay
+            .lock_reset
+            .and_then(|reset| lock_resets.iter().position(|name| name == reset.name()))
+            .unwrap_or(0);
+
+        let gameplay = &settings.gameplay;
+        let (lock_delays, current_lock_delay) = override_options(&[0, 250, 500, 750, 1000], gameplay.lock_delay_ms);
+        let (reset_limits, current_reset_limit) =
+            override_options(&[5, 10, 15, 30], gameplay.lock_reset_limit.map(u64::from));
+        let (ares, current_are) = override_options(&[0, 100, 200, 300, 500], gameplay.are_ms);
+        let (line_clear_delays, current_line_clear_delay) =
+            override_options(&[0, 200, 400, 700], gameplay.line_clear_delay_ms);
+
         Self {
             screen: MenuScreen::SettingsGameplay,
             selected: 0,
@@ -415,6 +870,115 @@ impl Menu {
                         step: 5,
                     },
                 },
+                MenuItem {
+                    label: "Garbage Delay (ms)".to_string(),
+                    item_type: MenuItemType::Number {
+                        key: SettingKey::GarbageDelayMs,
+                        value: settings.gameplay.garbage_delay_ms,
+                        min: 0,
+                        max: 3000,
+                        step: 100,
+                    },
+                },
+                MenuItem {
+                    label: "Rotation".to_string(),
+                    item_type: MenuItemType::Cycle {
+                        key: SettingKey::RotationSystem,
+                        options: rotation_systems,
+                        current: current_rotation,
+                    },
+                },
+                MenuItem {
+                    label: "Lock Delay (ms)".to_string(),
+                    item_type: MenuItemType::Cycle {
+                        key: SettingKey::LockDelayMs,
+                        options: lock_delays,
+                        current: current_lock_delay,
+                    },
+                },
+                MenuItem {
+                    label: "Lock Reset".to_string(),
+                    item_type: MenuItemType::Cycle {
+                        key: SettingKey::LockReset,
+                        options: lock_resets,
+                        current: current_lock_reset,
+                    },
+                },
+                MenuItem {
+                    label: "Lock Reset Limit".to_string(),
+                    item_type: MenuItemType::Cycle {
+                        key: SettingKey::LockResetLimit,
+                        options: reset_limits,
+                        current: current_reset_limit,
+                    },
+                },
+                MenuItem {
+                    label: "ARE (ms)".to_string(),
+                    item_type: MenuItemType::Cycle {
+                        key: SettingKey::AreMs,
+                        options: ares,
+                        current: current_are,
+                    },
+                },
+                MenuItem {
+                    label: "Line Clear Delay (ms)".to_string(),
+                    item_type: MenuItemType::Cycle {
+                        key: SettingKey::LineClearDelayMs,
+                        options: line_clear_delays,
+                        current: current_line_clear_delay,
+                    },
+                },
+                MenuItem {
+                    label: "Initial Rotation (IRS)".to_string(),
+                    item_type: MenuItemType::Toggle {
+                        key: SettingKey::Irs,
+                        value: settings.gameplay.irs,
+                    },
+                },
+                MenuItem {
+                    label: "Initial Hold (IHS)".to_string(),
+                    item_type: MenuItemType::Toggle {
+                        key: SettingKey::Ihs,
+                        value: settings.gameplay.ihs,
+                    },
+                },
+                MenuItem {
+                    label: "Cheese Messiness (%)".to_string(),
+                    item_type: MenuItemType::Number {
+                        key: SettingKey::CheeseMessiness,
+                        value: settings.gameplay.cheese_messiness as u64,
+                        min: 0,
+                        max: 100,
+                        step: 10,
+                    },
+                },
+                MenuItem {
+                    label: "Zen Gravity (level)".to_string(),
+                    item_type: MenuItemType::Number {
+                        key: SettingKey::ZenLevel,
+                        value: settings.gameplay.zen_level as u64,
+                        min: 0,
+                        max: 20,
+                        step: 1,
+                    },
+                },
+                MenuItem {
+                    label: "Practice Gravity (level)".to_string(),
+                    item_type: MenuItemType::Number {
+                        key: SettingKey::PracticeLevel,
+                        value: settings.gameplay.practice_level as u64,
+                        min: 0,
+                        max: 20,
+                        step: 1,
+                    },
+                },
+                MenuItem {
+                    label: "Ghost Race (PB)".to_string(),
+                    item_type: MenuItemType::Toggle {
+                        key: SettingKey::GhostRace,
+                        value: settings.gameplay.ghost_race,
+                    },
+                },
                 MenuItem {
                     label: "Back".to_string(),
                     item_type: MenuItemType::Button(MenuAction::Back),
@@ -475,6 +1039,17 @@ impl Menu {
         }
     }
 
+    /// Show a message as the last item, replacing the one shown before
+    pub fn show_message(&mut self, message: &str) {
+        if let Some(MenuItemType::Label { .. }) = self.items.last().map(|item| &item.item_type) {
+            self.items.pop();
+        }
+        self.items.push(MenuItem {
+            label: message.lines().next().unwrap_or_default().to_string(),
+            item_type: MenuItemType::Label { text: String::new() },
+        });
+    }
+
     pub fn move_up(&mut self) {
         if self.rebinding.is_some() {
             return; // Don't move while rebinding
@@ -611,6 +1186,11 @@ impl Menu {
         *self = match screen {
             MenuScreen::Main => Self::main_menu(),
             MenuScreen::ModeSelect => Self::mode_select(),
+            MenuScreen::Sprint => Self::sprint_menu(),
+            MenuScreen::CheeseRace => Self::cheese_race_menu(),
+            MenuScreen::Daily => Self::daily_menu(settings, None),
+            MenuScreen::CustomModes => Self::custom_modes_menu(),
+            MenuScreen::CustomBuilder => Self::custom_builder(),
             MenuScreen::Settings => Self::settings_menu(),
             MenuScreen::SettingsKeys => Self::settings_keys(settings),
             MenuScreen::SettingsVisual => Self::settings_visual(settings),
@@ -619,6 +1199,7 @@ impl Menu {
             MenuScreen::Multiplayer => Self::multiplayer_menu(),
             MenuScreen::HostGame => Self::host_game_menu(None),
             MenuScreen::JoinGame => Self::join_game_menu(),
+            MenuScreen::Replays => Self::replays_menu(),
             _ => Self::main_menu(),
         };
     }
@@ -628,6 +1209,11 @@ impl Menu {
         let prev = match self.screen {
             MenuScreen::Main => MenuScreen::Main,
             MenuScreen::ModeSelect => MenuScreen::Main,
+            MenuScreen::Sprint => MenuScreen::ModeSelect,
+            MenuScreen::CheeseRace => MenuScreen::ModeSelect,
+            MenuScreen::Daily => MenuScreen::ModeSelect,
+            MenuScreen::CustomModes => MenuScreen::ModeSelect,
+            MenuScreen::CustomBuilder => MenuScreen::CustomModes,
             MenuScreen::Settings => MenuScreen::Main,
             MenuScreen::SettingsKeys => MenuScreen::Settings,
             MenuScreen::SettingsVisual => MenuScreen::Settings,
@@ -636,6 +1222,7 @@ impl Menu {
             MenuScreen::Multiplayer => MenuScreen::ModeSelect,
             MenuScreen::HostGame => MenuScreen::Multiplayer,
             MenuScreen::JoinGame => MenuScreen::Multiplayer,
+            MenuScreen::Replays => MenuScreen::Main,
             _ => MenuScreen::Main,
         };
         self.go_to(prev, settings);
@@ -655,6 +1242,51 @@ enum SettingValue {
     Number(u64),
 }
 
+/// Gravity choices in the custom mode builder
+const GRAVITY_OPTIONS: [&str; 8] =
+    ["Guideline", "None", "Level 1", "Level 5", "Level 10", "Level 15", "Level 20", "20G"];
+
+/// Gravity curve for one of `GRAVITY_OPTIONS`
+fn gravity_option(option: &str) -> GravityCurve {
+    match option {
+        "None" => GravityCurve::Fixed(0.0),
+        "20G" => GravityCurve::Fixed(TWENTY_G),
+        _ => match option.strip_prefix("Level ").and_then(|level| level.parse().ok()) {
+            Some(level) => GravityCurve::Fixed(GravityCurve::Guideline.at_level(level)),
+            None => GravityCurve::Guideline,
+        },
+    }
+}
+
+/// Cycle options for a numeric rule override: "Mode Default", then `values`
+/// (plus the current override if it was set to something else by hand)
+fn override_options(values: &[u64], current: Option<u64>) -> (Vec<String>, usize) {
+    let mut values = values.to_vec();
+    if let Some(value) = current.filter(|value| !values.contains(value)) {
+        values.push(value);
+        values.sort_unstable();
+    }
+
+    let mut options = vec![MODE_DEFAULT.to_string()];
+    options.extend(values.iter().map(|value| value.to_string()));
+    let index = current
+        .and_then(|current| values.iter().position(|&value| value == current))
+        .map_or(0, |position| position + 1);
+    (options, index)
+}
+
+/// How long ago a replay was saved, e.g. "5 min ago"
+fn saved_ago(saved_ms: u128, now_ms: u128) -> String {
+    let mins = now_ms.saturating_sub(saved_ms) / 60_000;
+    match mins {
+        0 => "just now".to_string(),
+        1..=59 => format!("{} min ago", mins),
+        60..=1439 => format!("{} h ago", mins / 60),
+        1440..=2879 => "1 day ago".to_string(),
+        _ => format!("{} days ago", mins / 1440),
+    }
+}
+
 /// Apply a setting change to the Settings struct
 fn apply_setting(settings: &mut Settings, key: &SettingKey, value: &SettingValue) {
     match (key, value) {
@@ -670,6 +1302,46 @@ fn apply_setting(settings: &mut Settings, key: &SettingKey, value: &SettingValue
         (SettingKey::ArrMs, SettingValue::Number(v)) => {
             settings.gameplay.arr_ms = *v;
         }
+        (SettingKey::GarbageDelayMs, SettingValue::Number(v)) => {
+            settings.gameplay.garbage_delay_ms = *v;
+        }
+        (SettingKey::RotationSystem, SettingValue::String(v)) => {
+            settings.gameplay.rotation_system = RotationSystemKind::from_name(v);
+        }
+        // "Mode Default" doesn't parse, which clears the override
+        (SettingKey::LockDelayMs, SettingValue::String(v)) => {
+            settings.gameplay.lock_delay_ms = v.parse().ok();
+        }
+        (SettingKey::LockReset, SettingValue::String(v)) => {
+            settings.gameplay.lock_reset = LockReset::from_name(v);
+        }
+        (SettingKey::LockResetLimit, SettingValue::String(v)) => {
+            settings.gameplay.lock_reset_limit = v.parse().ok();
+        }
+        (SettingKey::AreMs, SettingValue::String(v)) => {
+            settings.gameplay.are_ms = v.parse().ok();
+        }
+        (SettingKey::LineClearDelayMs, SettingValue::String(v)) => {
+            settings.gameplay.line_clear_delay_ms = v.parse().ok();
+        }
+        (SettingKey::Irs, SettingValue::Bool(v)) => {
+            settings.gameplay.irs = *v;
+        }
+        (SettingKey::Ihs, SettingValue::Bool(v)) => {
+            settings.gameplay.ihs = *v;
+        }
+        (SettingKey::CheeseMessiness, SettingValue::Number(v)) => {
+            settings.gameplay.cheese_messiness = *v as u32;
+        }
+        (SettingKey::ZenLevel, SettingValue::Number(v)) => {
+            settings.gameplay.zen_level = *v as u32;
+        }
+        (SettingKey::PracticeLevel, SettingValue::Number(v)) => {
+            settings.gameplay.practice_level = *v as u32;
+        }
+        (SettingKey::GhostRace, SettingValue::Bool(v)) => {
+            settings.gameplay.ghost_race = *v;
+        }
         (SettingKey::BgmVolume, SettingValue::Number(v)) => {
             settings.audio.bgm_volume = *v as u32;
         }
@@ -697,9 +1369,14 @@ pub fn update_key_binding_pub(settings: &mut Settings, action: &str, keys: Vec<S
         "hard_drop" => settings.keys.hard_drop = keys,
         "rotate_cw" => settings.keys.rotate_cw = keys,
         "rotate_ccw" => settings.keys.rotate_ccw = keys,
+        "rotate_180" => settings.keys.rotate_180 = keys,
         "hold" => settings.keys.hold = keys,
         "pause" => settings.keys.pause = keys,
         "quit" => settings.keys.quit = keys,
+        "undo" => settings.keys.undo = keys,
+        "redo" => settings.keys.redo = keys,
+        "reset" => settings.keys.reset = keys,
+        "next_piece" => settings.keys.next_piece = keys,
         _ => {}
     }
 }
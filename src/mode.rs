@@ -1,25 +1,56 @@
-//! Game modes: Marathon, Sprint, Ultra, Versus
+//! Game modes: Marathon, Sprint, Ultra, Blitz, Versus, Master, Cheese Race,
+//! Survival, Zen, the Daily challenge, Practice and player-defined Custom
+//! modes
 
-use std::time::{Duration, Instant};
+use crate::custom::{CustomMode, Goal};
+use crate::daily::DAILY_LINES;
+use crate::game::frames_to_duration;
+use crate::gravity::GravityCurve;
+use crate::practice::PracticeState;
+use crate::randomizer::RandomizerKind;
+use crate::rotation::RotationSystemKind;
+use crate::rules::{LockReset, Rules};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 /// Available game modes
 #[non_exhaustive]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum GameMode {
     #[default]
     Marathon, // Endless, level increases every 10 lines
-    Sprint,   // Clear 40 lines as fast as possible
+    Sprint(u32), // Clear this many lines as fast as possible
     Ultra,    // Score as much as possible in 3 minutes
     Versus,   // Multiplayer battle mode
+    Master,   // Reach level 999 as speed climbs to 20G, graded on the way
+    Cheese(u32), // Dig through this many garbage lines as fast as possible
+    Survival, // Last as long as possible as garbage rises faster and faster
+    Zen,      // Endless and relaxed, topping out just clears the board
+    Blitz,    // Score attack in 2 minutes with fast level ups and a final bonus
+    Custom,   // Goal and rules from a `CustomMode`
+    Daily,    // 40 line race on the day's pieces, the same for everyone
+    Practice, // Sandbox with undo, redo and piece choice, no gravity by default
 }
 
 impl GameMode {
+    /// Sprint distances offered in the menu, in lines
+    pub const SPRINT_DISTANCES: [u32; 4] = [20, 40, 100, 1000];
+
     pub fn name(&self) -> &'static str {
         match self {
             GameMode::Marathon => "Marathon",
-            GameMode::Sprint => "Sprint",
+            GameMode::Sprint(_) => "Sprint",
             GameMode::Ultra => "Ultra",
             GameMode::Versus => "Versus",
+            GameMode::Master => "Master",
+            GameMode::Cheese(_) => "Cheese Race",
+            GameMode::Survival => "Survival",
+            GameMode::Zen => "Zen",
+            GameMode::Blitz => "Blitz",
+            GameMode::Custom => "Custom",
+            GameMode::Daily => "Daily",
+            GameMode::Practice => "Practice",
             _ => "Unknown",
         }
     }
@@ -27,9 +58,17 @@ impl GameMode {
     pub fn description(&self) -> &'static str {
         match self {
             GameMode::Marathon => "Endless mode - level up every 10 lines",
-            GameMode::Sprint => "Clear 40 lines as fast as possible",
+            GameMode::Sprint(_) => "Clear the lines as fast as possible",
             GameMode::Ultra => "Score as much as you can in 3 minutes",
             GameMode::Versus => "Battle another player online",
+            GameMode::Master => "Climb to level 999 at up to 20G for a grade",
+            GameMode::Cheese(_) => "Dig through the garbage as fast as possible",
+            GameMode::Survival => "Hold out as garbage rises faster and faster",
+            GameMode::Zen => "Play forever at your own pace",
+            GameMode::Blitz => "Score fast in 2 minutes, levels multiply the score",
+            GameMode::Custom => "Your own goal and rules",
+            GameMode::Daily => "Today's 40 line race, the same pieces for everyone",
+            GameMode::Practice => "Drill openers with undo, redo and your pick of pieces",
             _ => "",
         }
     }
@@ -37,37 +76,123 @@ impl GameMode {
     pub fn starting_level(&self) -> u32 {
         match self {
             GameMode::Marathon => 1,
-            GameMode::Sprint => 5,
+            GameMode::Sprint(_) => 5,
             GameMode::Ultra => 5,
             GameMode::Versus => 1,
             _ => 1,
         }
     }
 
+    /// Default rules for this mode
+    pub fn rules(&self) -> Rules {
+        match self {
+            // TGM: Arika rotation, history randomizer and step reset
+            // Delays here are the first section's, `MasterState` shortens them
+            GameMode::Master => Rules {
+                rotation_system: RotationSystemKind::Ars,
+                randomizer: RandomizerKind::Tgm,
+                gravity: GravityCurve::master(),
+                lock_delay_ms: frames_to_ms(30),
+                lock_reset: LockReset::Step,
+                are_ms: frames_to_ms(25),
+                line_clear_delay_ms: frames_to_ms(40),
+                ..Rules::default()
+            },
+            // Gravity stays put whatever the level, see `GameplaySettings::zen_level`
+            GameMode::Zen => Rules {
+                gravity: GravityCurve::Fixed(GravityCurve::Guideline.at_level(1)),
+                ..Rules::default()
+            },
+            // Pieces wait to be placed, see `GameplaySettings::practice_level`
+            GameMode::Practice => Rules {
+                gravity: GravityCurve::Fixed(0.0),
+                ..Rules::default()
+            },
+            // Levels come quickly, and gravity climbs with them
+            GameMode::Blitz => Rules {
+                lines_per_level: 5,
+                ..Rules::default()
+            },
+            _ => Rules::default(),
+        }
+    }
+
     /// Single-player modes only
     pub fn single_player() -> &'static [GameMode] {
-        &[GameMode::Marathon, GameMode::Sprint, GameMode::Ultra]
+        &[
+            GameMode::Marathon,
+            GameMode::Sprint(40),
+            GameMode::Ultra,
+            GameMode::Master,
+            GameMode::Cheese(18),
+            GameMode::Survival,
+            GameMode::Zen,
+            GameMode::Blitz,
+            GameMode::Custom,
+            GameMode::Daily,
+            GameMode::Practice,
+        ]
     }
 
     pub fn all() -> &'static [GameMode] {
-        &[GameMode::Marathon, GameMode::Sprint, GameMode::Ultra, GameMode::Versus]
+        &[
+            GameMode::Marathon,
+            GameMode::Sprint(40),
+            GameMode::Ultra,
+            GameMode::Versus,
+            GameMode::Master,
+            GameMode::Cheese(18),
+            GameMode::Survival,
+            GameMode::Zen,
+            GameMode::Blitz,
+            GameMode::Custom,
+            GameMode::Daily,
+            GameMode::Practice,
+        ]
     }
 
     pub fn is_multiplayer(&self) -> bool {
         matches!(self, GameMode::Versus)
     }
+
+    /// Whether topping out clears the board instead of ending the game
+    pub fn endless(&self) -> bool {
+        matches!(self, GameMode::Zen | GameMode::Practice)
+    }
+
+    /// Whether results are ranked by time rather than score
+    pub fn ranked_by_time(&self) -> bool {
+        matches!(self, GameMode::Sprint(_) | GameMode::Cheese(_) | GameMode::Survival | GameMode::Daily)
+    }
 }
 
 /// Mode-specific game state
 #[derive(Debug, Clone)]
 pub struct ModeState {
     pub mode: GameMode,
-    pub start_time: Option<Instant>,
+    /// Game clock time when play started
+    pub start_time: Option<Duration>,
     pub elapsed: Duration,
-    /// For Sprint: lines remaining
+    /// For Sprint and Daily: lines to clear
     pub target_lines: u32,
-    /// For Ultra: time limit
+    /// For Sprint: time taken to reach every `SPLIT_LINES` lines
+    pub splits: Vec<Duration>,
+    /// For Ultra and Blitz: time limit
     pub time_limit: Duration,
+    /// For Master: level, grade and credit roll
+    pub master: Option<MasterState>,
+    /// For Cheese Race: garbage dug and left to dig
+    pub cheese: Option<CheeseState>,
+    /// For Survival: the rising garbage timer
+    pub survival: Option<SurvivalState>,
+    /// For Blitz: bonus points awarded at the lock out, once time is up
+    pub lockout_bonus: Option<u64>,
+    /// For Custom: the mode being played
+    pub custom: Option<CustomMode>,
+    /// For Practice: undo and redo history
+    pub practice: Option<PracticeState>,
+    /// For Daily: the day being played, in days since the epoch (UTC)
+    pub day: Option<u64>,
 }
 
 impl ModeState {
@@ -76,20 +201,70 @@ impl ModeState {
             mode,
             start_time: None,
             elapsed: Duration::ZERO,
-            target_lines: 40,
-            time_limit: Duration::from_secs(180), // 3 minutes
+            target_lines: match mode {
+                GameMode::Sprint(lines) => lines,
+                GameMode::Daily => DAILY_LINES,
+                _ => 0,
+            },
+            splits: Vec::new(),
+            time_limit: match mode {
+                GameMode::Blitz => Duration::from_secs(120),
+                _ => Duration::from_secs(180), // 3 minutes
+            },
+            master: (mode == GameMode::Master).then(MasterState::default),
+            cheese: match mode {
+                GameMode::Cheese(target) => Some(CheeseState::new(target)),
+                _ => None,
+            },
+            survival: (mode == GameMode::Survival).then(SurvivalState::default),
+            lockout_bonus: None,
+            custom: None,
+            practice: (mode == GameMode::Practice).then(PracticeState::default),
+            day: None,
         }
     }
 
-    /// Start the timer
-    pub fn start(&mut self) {
-        self.start_time = Some(Instant::now());
+    /// Lines between Sprint split times
+    pub const SPLIT_LINES: u32 = 10;
+
+    /// State for a game of a custom mode
+    pub fn for_custom(custom: CustomMode) -> Self {
+        let mut state = Self::new(GameMode::Custom);
+        match custom.goal {
+            Goal::Lines(lines) => state.target_lines = lines,
+            Goal::Time(secs) => state.time_limit = Duration::from_secs(secs),
+            _ => {}
+        }
+        state.custom = Some(custom);
+        state
+    }
+
+    /// Goal of the custom mode being played (None for other modes)
+    pub fn custom_goal(&self) -> Option<Goal> {
+        self.custom.as_ref().map(|custom| custom.goal)
+    }
+
+    /// Start the timer at the given game clock time
+    pub fn start(&mut self, now: Duration) {
+        self.start_time = Some(now);
     }
 
-    /// Update elapsed time
-    pub fn update(&mut self) {
+    /// Update elapsed time from the game clock
+    pub fn update(&mut self, now: Duration) {
         if let Some(start) = self.start_time {
-            self.elapsed = start.elapsed();
+            self.elapsed = now.saturating_sub(start);
+        }
+    }
+
+    /// Sprint: record a split for every `SPLIT_LINES` lines passed since
+    /// the last one
+    pub fn record_splits(&mut self, lines_cleared: u32) {
+        if !matches!(self.mode, GameMode::Sprint(_)) {
+            return;
+        }
+        let reached = lines_cleared.min(self.target_lines) / Self::SPLIT_LINES;
+        while (self.splits.len() as u32) < reached {
+            self.splits.push(self.elapsed);
         }
     }
 
@@ -97,17 +272,22 @@ impl ModeState {
     pub fn is_complete(&self, lines_cleared: u32) -> bool {
         match self.mode {
             GameMode::Marathon => false, // Never ends automatically
-            GameMode::Sprint => lines_cleared >= self.target_lines,
-            GameMode::Ultra => self.elapsed >= self.time_limit,
+            GameMode::Sprint(_) | GameMode::Daily => lines_cleared >= self.target_lines,
+            GameMode::Ultra | GameMode::Blitz => self.elapsed >= self.time_limit,
             GameMode::Versus => false, // Ends when opponent tops out or we do
+            GameMode::Master => self.master.as_ref().is_some_and(|master| master.roll_finished(self.elapsed)),
+            GameMode::Cheese(_) => self.cheese.as_ref().is_some_and(|cheese| cheese.remaining() == 0),
             _ => false,
         }
     }
 
-    /// Get remaining time for Ultra mode (None for other modes)
+    /// Get remaining time for Ultra and Blitz (None for other modes)
     pub fn time_remaining(&self) -> Option<Duration> {
         match self.mode {
-            GameMode::Ultra => Some(self.time_limit.saturating_sub(self.elapsed)),
+            GameMode::Ultra | GameMode::Blitz => Some(self.time_limit.saturating_sub(self.elapsed)),
+            GameMode::Custom if matches!(self.custom_goal(), Some(Goal::Time(_))) => {
+                Some(self.time_limit.saturating_sub(self.elapsed))
+            }
             _ => None,
         }
     }
@@ -115,11 +295,19 @@ impl ModeState {
     /// Get lines remaining for Sprint mode (None for other modes)
     pub fn lines_remaining(&self, lines_cleared: u32) -> Option<u32> {
         match self.mode {
-            GameMode::Sprint => Some(self.target_lines.saturating_sub(lines_cleared)),
+            GameMode::Sprint(_) | GameMode::Daily => Some(self.target_lines.saturating_sub(lines_cleared)),
+            GameMode::Custom if matches!(self.custom_goal(), Some(Goal::Lines(_))) => {
+                Some(self.target_lines.saturating_sub(lines_cleared))
+            }
             _ => None,
         }
     }
 
+    /// Get garbage lines remaining for Cheese Race (None for other modes)
+    pub fn garbage_remaining(&self) -> Option<u32> {
+        self.cheese.as_ref().map(CheeseState::remaining)
+    }
+
     /// Format elapsed time as MM:SS.mmm
     pub fn format_time(&self) -> String {
         let total_millis = self.elapsed.as_millis();
@@ -129,7 +317,15 @@ impl ModeState {
         format!("{:02}:{:02}.{:03}", minutes, seconds, millis)
     }
 
-    /// Format remaining time for Ultra mode
+    /// Blitz lock out: bonus points for the level reached, 10% of the
+    /// score for each level past the first
+    pub fn lock_out(&mut self, points: u64, level: u32) -> u64 {
+        let bonus = points * level.saturating_sub(1) as u64 / 10;
+        self.lockout_bonus = Some(bonus);
+        bonus
+    }
+
+    /// Format remaining time for Ultra and Blitz
     pub fn format_remaining(&self) -> Option<String> {
         self.time_remaining().map(|remaining| {
             let total_secs = remaining.as_secs();
@@ -139,3 +335,406 @@ impl ModeState {
         })
     }
 }
+
+/// Timing at the current Master level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MasterTiming {
+    pub are: Duration,
+    pub line_clear_delay: Duration,
+    pub lock_delay: Duration,
+}
+
+/// Master mode progress (TGM style)
+///
+/// The level goes up by one for each piece that spawns and by the number of
+/// lines cleared, but a spawn can't take it past the end of a section
+/// (x99, or 998 before the end). Reaching 999 starts the credit roll.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MasterState {
+    /// Section level, 0 to 999
+    pub level: u32,
+    /// TGM score used for grading
+    pub grade_points: u64,
+    /// TGM combo multiplier (1 when not in a combo)
+    combo: u64,
+    /// Whether the GM checkpoints were all met so far
+    gm_eligible: bool,
+    /// Earned the GM grade by reaching 999 with every checkpoint met
+    pub grand_master: bool,
+    /// Elapsed time when the credit roll started
+    pub roll_start: Option<Duration>,
+}
+
+impl Default for MasterState {
+    fn default() -> Self {
+        Self {
+            level: 0,
+            grade_points: 0,
+            combo: 1,
+            gm_eligible: true,
+            grand_master: false,
+            roll_start: None,
+        }
+    }
+}
+
+impl MasterState {
+    /// Level that ends the game and starts the credit roll
+    pub const MAX_LEVEL: u32 = 999;
+    /// Length of the credit roll
+    pub const ROLL_DURATION: Duration = Duration::from_secs(54);
+
+    /// Grade thresholds in grade points (TGM), lowest grade first
+    const GRADES: [(u64, &'static str); 18] = [
+        (0, "9"),
+        (400, "8"),
+        (800, "7"),
+        (1400, "6"),
+        (2000, "5"),
+        (3500, "4"),
+        (5500, "3"),
+        (8000, "2"),
+        (12000, "1"),
+        (16000, "S1"),
+        (22000, "S2"),
+        (30000, "S3"),
+        (40000, "S4"),
+        (52000, "S5"),
+        (66000, "S6"),
+        (82000, "S7"),
+        (100000, "S8"),
+        (120000, "S9"),
+    ];
+
+    /// GM checkpoints: (level, latest time, minimum grade points)
+    const GM_CHECKPOINTS: [(u32, Duration, u64); 3] = [
+        (300, Duration::from_secs(4 * 60 + 15), 12000),
+        (500, Duration::from_secs(7 * 60 + 30), 40000),
+        (999, Duration::from_secs(13 * 60 + 30), 126000),
+    ];
+
+    /// A new piece spawned
+    pub fn on_spawn(&mut self, elapsed: Duration) {
+        if self.level % 100 != 99 && self.level != Self::MAX_LEVEL - 1 && self.roll_start.is_none() {
+            self.set_level(self.level + 1, elapsed);
+        }
+    }
+
+    /// A piece locked, clearing `lines` (TGM scoring and level up)
+    /// Returns true if this reached level 999 and the credit roll should start
+    pub fn on_lock(&mut self, lines: u32, soft_drop: u32, all_clear: bool, elapsed: Duration) -> bool {
+        if lines == 0 {
+            self.combo = 1;
+            return false;
+        }
+        if self.roll_start.is_some() {
+            return false;
+        }
+
+        // TGM: (ceil((level + lines) / 4) + soft) * lines * combo * bravo
+        self.combo += 2 * lines as u64 - 2;
+        let bravo = if all_clear { 4 } else { 1 };
+        let base = (self.level + lines).div_ceil(4) as u64 + soft_drop as u64;
+        self.grade_points += base * lines as u64 * self.combo * bravo;
+
+        self.set_level((self.level + lines).min(Self::MAX_LEVEL), elapsed);
+        if self.level == Self::MAX_LEVEL {
+            self.grand_master = self.gm_eligible;
+            self.roll_start = Some(elapsed);
+            return true;
+        }
+        false
+    }
+
+    /// Move to a new level, checking any GM checkpoints passed on the way
+    fn set_level(&mut self, level: u32, elapsed: Duration) {
+        for (checkpoint, time, points) in Self::GM_CHECKPOINTS {
+            if self.level < checkpoint && level >= checkpoint {
+                self.gm_eligible &= elapsed <= time && self.grade_points >= points;
+            }
+        }
+        self.level = level;
+    }
+
+    /// Current grade name
+    pub fn grade(&self) -> &'static str {
+        if self.grand_master {
+            return "GM";
+        }
+        Self::GRADES
+            .iter()
+            .rev()
+            .find(|&&(points, _)| self.grade_points >= points)
+            .map_or("9", |&(_, name)| name)
+    }
+
+    /// Level the current section stops at
+    pub fn section_end(&self) -> u32 {
+        (self.level / 100 * 100 + 100).min(Self::MAX_LEVEL)
+    }
+
+    /// Whether the credit roll is on (the stack is invisible)
+    pub fn in_roll(&self) -> bool {
+        self.roll_start.is_some()
+    }
+
+    /// Time left in the credit roll
+    pub fn roll_remaining(&self, elapsed: Duration) -> Option<Duration> {
+        self.roll_start
+            .map(|start| Self::ROLL_DURATION.saturating_sub(elapsed.saturating_sub(start)))
+    }
+
+    /// Whether the credit roll has been survived
+    pub fn roll_finished(&self, elapsed: Duration) -> bool {
+        self.roll_remaining(elapsed).is_some_and(|remaining| remaining.is_zero())
+    }
+
+    /// Delays at the current level, shortening in the later sections
+    pub fn timing(&self) -> MasterTiming {
+        // (ARE, line clear delay, lock delay) in frames
+        let (are, line_clear_delay, lock_delay) = match self.level {
+            0..=499 => (25, 40, 30),
+            500..=599 => (25, 25, 30),
+            600..=699 => (25, 16, 30),
+            700..=799 => (16, 12, 30),
+            800..=899 => (12, 6, 30),
+            _ => (12, 6, 17),
+        };
+        MasterTiming {
+            are: frames_to_duration(are),
+            line_clear_delay: frames_to_duration(line_clear_delay),
+            lock_delay: frames_to_duration(lock_delay),
+        }
+    }
+}
+
+/// Cheese Race progress
+///
+/// The board starts with garbage rows at the bottom and is topped back up
+/// after each lock so that `MIN_ROWS` stay on screen, until all `target`
+/// rows have been added. The race ends when the last one is cleared.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheeseState {
+    /// Garbage lines to clear
+    pub target: u32,
+    /// Garbage lines cleared so far
+    pub cleared: u32,
+    /// Garbage rows added to the board so far
+    pub added: u32,
+    /// Hole column of the last row added
+    hole: Option<usize>,
+}
+
+impl CheeseState {
+    /// Race lengths offered in the menu
+    pub const TARGETS: [u32; 3] = [10, 18, 100];
+    /// Garbage rows kept on screen while there are more to add
+    pub const MIN_ROWS: u32 = 10;
+
+    pub fn new(target: u32) -> Self {
+        Self {
+            target,
+            cleared: 0,
+            added: 0,
+            hole: None,
+        }
+    }
+
+    /// Garbage lines left to clear
+    pub fn remaining(&self) -> u32 {
+        self.target.saturating_sub(self.cleared)
+    }
+
+    /// Rows to add to top the board back up, given the garbage rows on it
+    pub fn rows_to_add(&self, on_board: u32) -> u32 {
+        Self::MIN_ROWS
+            .saturating_sub(on_board)
+            .min(self.target.saturating_sub(self.added))
+    }
+
+    /// Hole column for the next garbage row: with `messiness` percent chance
+    /// it moves to a different column, otherwise it lines up with the last one
+    pub fn next_hole(&mut self, rng: &mut impl Rng, width: usize, messiness: u32) -> usize {
+        let hole = match self.hole {
+            Some(last) if width > 1 && rng.gen_range(0..100) < messiness => {
+                // Any column but the last one
+                (last + rng.gen_range(1..width)) % width
+            }
+            Some(last) => last,
+            None => rng.gen_range(0..width),
+        };
+        self.hole = Some(hole);
+        self.added += 1;
+        hole
+    }
+}
+
+/// Survival progress: garbage rows rise from below on a timer that speeds up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SurvivalState {
+    /// Elapsed time when the next row rises
+    pub next_rise: Duration,
+    /// Garbage rows risen so far
+    pub rows_risen: u32,
+}
+
+impl Default for SurvivalState {
+    fn default() -> Self {
+        Self {
+            next_rise: Self::interval_at(Duration::ZERO),
+            rows_risen: 0,
+        }
+    }
+}
+
+impl SurvivalState {
+    /// Time between rows at the start
+    const START_INTERVAL: Duration = Duration::from_secs(4);
+    /// Fastest the rows ever rise
+    const MIN_INTERVAL: Duration = Duration::from_millis(500);
+    /// The interval shrinks by 10% each period
+    const SPEEDUP_PERIOD: Duration = Duration::from_secs(30);
+
+    /// Time between rows at an elapsed time
+    pub fn interval_at(elapsed: Duration) -> Duration {
+        let speedups = (elapsed.as_millis() / Self::SPEEDUP_PERIOD.as_millis()) as i32;
+        Self::START_INTERVAL
+            .mul_f64(0.9f64.powi(speedups))
+            .max(Self::MIN_INTERVAL)
+    }
+
+    /// Number of rows due to rise by `elapsed`, scheduling the ones after
+    pub fn rows_due(&mut self, elapsed: Duration) -> u32 {
+        let mut rows = 0;
+        while elapsed >= self.next_rise {
+            rows += 1;
+            self.next_rise += Self::interval_at(self.next_rise);
+        }
+        self.rows_risen += rows;
+        rows
+    }
+
+    /// Time until the next row rises
+    pub fn time_to_rise(&self, elapsed: Duration) -> Duration {
+        self.next_rise.saturating_sub(elapsed)
+    }
+}
+
+/// Milliseconds in a number of frames, rounded down so the delay ends on that frame
+fn frames_to_ms(frames: u64) -> u64 {
+    frames_to_duration(frames).as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_master_section_stop() {
+        let mut master = MasterState::default();
+        for _ in 0..150 {
+            master.on_spawn(Duration::ZERO);
+        }
+        // Spawns stop at the end of the section
+        assert_eq!(master.level, 99);
+        // Clearing lines breaks through
+        master.on_lock(2, 0, false, Duration::ZERO);
+        assert_eq!(master.level, 101);
+        assert_eq!(master.section_end(), 200);
+    }
+
+    #[test]
+    fn test_master_roll_and_grade() {
+        let mut master = MasterState { level: 997, ..MasterState::default() };
+        assert!(master.on_lock(4, 0, false, Duration::from_secs(600)));
+        assert_eq!(master.level, MasterState::MAX_LEVEL);
+        assert!(master.in_roll());
+        // Far too few grade points for GM
+        assert_ne!(master.grade(), "GM");
+        assert!(!master.roll_finished(Duration::from_secs(620)));
+        assert!(master.roll_finished(Duration::from_secs(600) + MasterState::ROLL_DURATION));
+    }
+
+    #[test]
+    fn test_sprint_splits() {
+        let mut state = ModeState::new(GameMode::Sprint(20));
+        assert_eq!(state.target_lines, 20);
+        state.elapsed = Duration::from_secs(5);
+        state.record_splits(9);
+        assert!(state.splits.is_empty());
+        // A tetris can pass a split
+        state.record_splits(12);
+        state.elapsed = Duration::from_secs(9);
+        state.record_splits(23);
+        assert_eq!(state.splits, [Duration::from_secs(5), Duration::from_secs(9)]);
+        assert!(state.is_complete(23));
+    }
+
+    #[test]
+    fn test_cheese_refill() {
+        let cheese = CheeseState::new(18);
+        assert_eq!(cheese.rows_to_add(0), CheeseState::MIN_ROWS);
+        // Never adds more rows than the race has left
+        let cheese = CheeseState { added: 15, ..CheeseState::new(18) };
+        assert_eq!(cheese.rows_to_add(4), 3);
+        assert_eq!(cheese.rows_to_add(9), 1);
+    }
+
+    #[test]
+    fn test_cheese_messiness() {
+        use rand::SeedableRng;
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(5);
+
+        // No messiness: one straight well
+        let mut cheese = CheeseState::new(10);
+        let first = cheese.next_hole(&mut rng, 10, 0);
+        assert!((0..9).all(|_| cheese.next_hole(&mut rng, 10, 0) == first));
+
+        // Full messiness: the hole moves every row
+        let mut cheese = CheeseState::new(10);
+        let mut last = cheese.next_hole(&mut rng, 10, 100);
+        for _ in 0..9 {
+            let hole = cheese.next_hole(&mut rng, 10, 100);
+            assert_ne!(hole, last);
+            assert!(hole < 10);
+            last = hole;
+        }
+        assert_eq!(cheese.added, 10);
+    }
+
+    #[test]
+    fn test_survival_speeds_up() {
+        assert_eq!(SurvivalState::interval_at(Duration::ZERO), Duration::from_secs(4));
+        assert!(SurvivalState::interval_at(Duration::from_secs(60)) < Duration::from_secs(4));
+        assert_eq!(SurvivalState::interval_at(Duration::from_secs(3600)), Duration::from_millis(500));
+
+        let mut survival = SurvivalState::default();
+        assert_eq!(survival.rows_due(Duration::from_secs(3)), 0);
+        assert_eq!(survival.rows_due(Duration::from_secs(4)), 1);
+        // Catches up on several rows at once
+        assert_eq!(survival.rows_due(Duration::from_secs(13)), 2);
+        assert_eq!(survival.rows_risen, 3);
+        assert_eq!(survival.time_to_rise(Duration::from_secs(13)), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn test_blitz_lock_out() {
+        let mut state = ModeState::new(GameMode::Blitz);
+        assert_eq!(state.format_remaining().as_deref(), Some("02:00"));
+        state.elapsed = Duration::from_secs(120);
+        assert!(state.is_complete(0));
+        // Level 6: 50% on top
+        assert_eq!(state.lock_out(10_000, 6), 5000);
+        assert_eq!(state.lockout_bonus, Some(5000));
+    }
+
+    #[test]
+    fn test_master_grade_thresholds() {
+        let mut master = MasterState::default();
+        assert_eq!(master.grade(), "9");
+        master.grade_points = 16000;
+        assert_eq!(master.grade(), "S1");
+        master.grade_points = 500_000;
+        assert_eq!(master.grade(), "S9");
+    }
+}
@@ -7,7 +7,8 @@
 //! 4. On piece lock: send board state + garbage
 //! 5. On game over: send result
 
-use crate::board::{Board, Cell, BOARD_HEIGHT, BOARD_WIDTH};
+use tetrs::board::{Board, Cell, BOARD_HEIGHT, BOARD_WIDTH};
+use tetrs::tetromino::TetrominoType;
 use iroh::{Endpoint, NodeAddr};
 use iroh_base::ticket::NodeTicket;
 use serde::{Deserialize, Serialize};
@@ -32,8 +33,11 @@ pub enum GameMessage {
     Countdown { value: u8 },
     /// Board state update (sent on piece lock)
     BoardState {
-        /// Flattened board cells as color indices
+        /// Flattened board cells as color indices, bottom row first
         cells: Vec<u8>,
+        /// Board columns, to unflatten `cells`
+        #[serde(default = "default_board_width")]
+        width: usize,
         /// Current score
         score: u64,
         /// Lines cleared total
@@ -55,6 +59,11 @@ pub enum GameMessage {
     Disconnect,
 }
 
+/// Peers that predate runtime board sizes always send standard boards
+fn default_board_width() -> usize {
+    BOARD_WIDTH
+}
+
 /// Connection role
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Role {
@@ -88,7 +97,8 @@ pub enum ConnectionState {
 #[derive(Debug, Clone)]
 pub struct OpponentState {
     pub name: String,
-    pub board: [[Cell; BOARD_WIDTH]; BOARD_HEIGHT],
+    /// Visible rows, bottom first
+    pub board: Vec<Vec<Cell>>,
     pub score: u64,
     pub lines: u32,
     pub level: u32,
@@ -99,7 +109,7 @@ impl Default for OpponentState {
     fn default() -> Self {
         Self {
             name: "Opponent".to_string(),
-            board: [[Cell::Empty; BOARD_WIDTH]; BOARD_HEIGHT],
+            board: empty_board(),
             score: 0,
             lines: 0,
             level: 1,
@@ -110,62 +120,65 @@ impl Default for OpponentState {
 
 impl OpponentState {
     /// Update from a BoardState message
-    pub fn update_from_message(&mut self, cells: &[u8], score: u64, lines: u32, level: u32) {
+    pub fn update_from_message(&mut self, cells: &[u8], width: usize, score: u64, lines: u32, level: u32) {
         self.score = score;
         self.lines = lines;
         self.level = level;
 
-        // Decode cells (0 = empty, 1-7 = piece colors)
-        for row in 0..BOARD_HEIGHT {
-            for col in 0..BOARD_WIDTH {
-                let idx = row * BOARD_WIDTH + col;
-                if idx < cells.len() {
-                    self.board[row][col] = cell_from_index(cells[idx]);
-                }
-            }
+        // Decode cells (0 = empty, 1-7 = piece colors, 8 = garbage)
+        if width > 0 {
+            self.board = cells
+                .chunks_exact(width)
+                .map(|row| row.iter().copied().map(cell_from_index).collect())
+                .collect();
         }
     }
 }
 
+/// Empty standard-size board for an opponent we haven't heard from yet
+fn empty_board() -> Vec<Vec<Cell>> {
+    vec![vec![Cell::Empty; BOARD_WIDTH]; BOARD_HEIGHT]
+}
+
 /// Convert cell to index for network transmission
 pub fn cell_to_index(cell: &Cell) -> u8 {
     match cell {
         Cell::Empty => 0,
-        Cell::Filled(color) => {
-            use ratatui::style::Color;
-            match color {
-                Color::Cyan => 1,    // I
-                Color::Yellow => 2,  // O
-                Color::Magenta => 3, // T
-                Color::Green => 4,   // S
-                Color::Red => 5,     // Z
-                Color::Blue => 6,    // J
-                _ => 7,              // L (orange) or other
-            }
-        }
+        Cell::Filled(piece_type) => match piece_type {
+            TetrominoType::I => 1,
+            TetrominoType::O => 2,
+            TetrominoType::T => 3,
+            TetrominoType::S => 4,
+            TetrominoType::Z => 5,
+            TetrominoType::J => 6,
+            TetrominoType::L => 7,
+        },
+        Cell::Garbage => 8,
+        Cell::Bone => 9,
     }
 }
 
 /// Convert index back to cell
 pub fn cell_from_index(index: u8) -> Cell {
-    use ratatui::style::Color;
     match index {
-        0 => Cell::Empty,
-        1 => Cell::Filled(Color::Cyan),
-        2 => Cell::Filled(Color::Yellow),
-        3 => Cell::Filled(Color::Magenta),
-        4 => Cell::Filled(Color::Green),
-        5 => Cell::Filled(Color::Red),
-        6 => Cell::Filled(Color::Blue),
-        _ => Cell::Filled(Color::Rgb(255, 165, 0)),
+        1 => Cell::Filled(TetrominoType::I),
+        2 => Cell::Filled(TetrominoType::O),
+        3 => Cell::Filled(TetrominoType::T),
+        4 => Cell::Filled(TetrominoType::S),
+        5 => Cell::Filled(TetrominoType::Z),
+        6 => Cell::Filled(TetrominoType::J),
+        7 => Cell::Filled(TetrominoType::L),
+        8 => Cell::Garbage,
+        9 => Cell::Bone,
+        _ => Cell::Empty,
     }
 }
 
 /// Encode board state for transmission
 pub fn encode_board(board: &Board) -> Vec<u8> {
-    let mut cells = Vec::with_capacity(BOARD_HEIGHT * BOARD_WIDTH);
-    for row in 0..BOARD_HEIGHT {
-        for col in 0..BOARD_WIDTH {
+    let mut cells = Vec::with_capacity(board.height() * board.width());
+    for row in 0..board.height() {
+        for col in 0..board.width() {
             let cell = board.get(row as i32, col as i32).unwrap_or(Cell::Empty);
             cells.push(cell_to_index(&cell));
         }
@@ -196,6 +209,7 @@ pub enum NetEvent {
     /// Opponent's board state updated
     BoardUpdate {
         cells: Vec<u8>,
+        width: usize,
         score: u64,
         lines: u32,
         level: u32,
@@ -349,6 +363,7 @@ impl MultiplayerSession {
     pub fn send_board_state_raw(&self, board: &Board, score: u64, lines: u32, level: u32) {
         self.send(GameMessage::BoardState {
             cells: encode_board(board),
+            width: board.width(),
             score,
             lines,
             level,
@@ -356,7 +371,7 @@ impl MultiplayerSession {
     }
 
     /// Send our board state (from game)
-    pub fn send_board_state(&self, game: &crate::game::Game) {
+    pub fn send_board_state(&self, game: &tetrs::game::Game) {
         self.send_board_state_raw(
             &game.board,
             game.score.points,
@@ -410,7 +425,7 @@ impl MultiplayerSession {
         self.opponent.score = 0;
         self.opponent.lines = 0;
         self.opponent.level = 1;
-        self.opponent.board = [[Cell::Empty; BOARD_WIDTH]; BOARD_HEIGHT];
+        self.opponent.board = empty_board();
         self.pending_garbage = 0;
         self.countdown_start = None;
         // Generate new seed for host
@@ -430,41 +445,6 @@ impl MultiplayerSession {
     }
 }
 
-/// Calculate garbage lines to send based on lines cleared
-/// Standard guideline:
-/// - Single: 0 garbage
-/// - Double: 1 garbage
-/// - Triple: 2 garbage
-/// - Tetris: 4 garbage
-/// - T-spin single: 2 garbage
-/// - T-spin double: 4 garbage
-/// - T-spin triple: 6 garbage
-/// - Back-to-back bonus: +1 garbage
-/// - Combo bonus: +combo count
-pub fn calculate_garbage(lines: u32, is_tspin: bool, combo: u32, back_to_back: bool) -> u8 {
-    let base = if is_tspin {
-        match lines {
-            1 => 2,
-            2 => 4,
-            3 => 6,
-            _ => 0,
-        }
-    } else {
-        match lines {
-            1 => 0,
-            2 => 1,
-            3 => 2,
-            4 => 4,
-            _ => 0,
-        }
-    };
-
-    let b2b_bonus = if back_to_back && (lines == 4 || is_tspin) { 1 } else { 0 };
-    let combo_bonus = if combo > 0 { combo.min(10) } else { 0 };
-
-    (base + b2b_bonus + combo_bonus as u8).min(12) // Cap at 12 lines
-}
-
 /// Serialize a message to bytes with length prefix
 fn encode_message(msg: &GameMessage) -> Vec<u8> {
     let json = serde_json::to_vec(msg).unwrap_or_default();
@@ -723,8 +703,8 @@ async fn message_loop(
                     Some(GameMessage::Countdown { value }) => {
                         let _ = event_tx.send(NetEvent::Countdown { value });
                     }
-                    Some(GameMessage::BoardState { cells, score, lines, level }) => {
-                        let _ = event_tx.send(NetEvent::BoardUpdate { cells, score, lines, level });
+                    Some(GameMessage::BoardState { cells, width, score, lines, level }) => {
+                        let _ = event_tx.send(NetEvent::BoardUpdate { cells, width, score, lines, level });
                     }
                     Some(GameMessage::Garbage { lines }) => {
                         let _ = event_tx.send(NetEvent::GarbageReceived { lines });
@@ -783,3 +763,31 @@ pub fn spawn_join(
 
     Ok((cmd_tx, event_rx))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cell_index_round_trip() {
+        let mut cells = vec![Cell::Empty, Cell::Garbage, Cell::Bone];
+        cells.extend(TetrominoType::all().map(Cell::Filled));
+        for cell in cells {
+            assert_eq!(cell_from_index(cell_to_index(&cell)), cell);
+        }
+    }
+
+    #[test]
+    fn test_board_round_trip() {
+        let mut board = Board::with_size(6, 30);
+        board.set(0, 5, Cell::Garbage);
+        board.set(29, 0, Cell::Filled(TetrominoType::T));
+
+        let mut opponent = OpponentState::default();
+        opponent.update_from_message(&encode_board(&board), board.width(), 0, 0, 1);
+        assert_eq!(opponent.board[0].len(), 6);
+        assert_eq!(opponent.board.len(), 30);
+        assert_eq!(opponent.board[0][5], Cell::Garbage);
+        assert_eq!(opponent.board[29][0], Cell::Filled(TetrominoType::T));
+    }
+}
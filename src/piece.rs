@@ -1,7 +1,7 @@
 //! Active falling piece logic
 
-use crate::board::Board;
-use crate::srs::get_wall_kicks;
+use crate::board::{Board, BOARD_HEIGHT, BOARD_WIDTH};
+use crate::rotation::RotationSystemKind;
 use crate::tetromino::{Rotation, RotationDirection, TetrominoType};
 
 /// An active falling piece
@@ -16,28 +16,54 @@ pub struct Piece {
     pub row: i32,
     pub col: i32,
     /// Which wall kick was used for the last rotation (for T-spin detection)
-    /// 0 = no kick, 1-5 = kick index
+    /// 0 = no kick, 1-6 = kick index
     pub last_kick: u8,
+    /// Direction of the last rotation, if the last successful move was one
+    pub last_rotation: Option<RotationDirection>,
+    /// Rotation system for shapes and kicks
+    pub system: RotationSystemKind,
 }
 
 impl Piece {
-    /// Create a new piece at spawn position
+    /// Create a new SRS piece at spawn position
     pub fn new(piece_type: TetrominoType) -> Self {
-        let (row, col) = piece_type.spawn_position();
+        Self::with_system(piece_type, RotationSystemKind::Srs)
+    }
+
+    /// Create a new piece at the spawn position of a rotation system
+    pub fn with_system(piece_type: TetrominoType, system: RotationSystemKind) -> Self {
+        Self::spawn_at(piece_type, system, BOARD_WIDTH, BOARD_HEIGHT)
+    }
+
+    /// Create a new piece at its spawn position on a board of any size
+    pub fn spawn(piece_type: TetrominoType, system: RotationSystemKind, board: &Board) -> Self {
+        Self::spawn_at(piece_type, system, board.width(), board.height())
+    }
+
+    fn spawn_at(piece_type: TetrominoType, system: RotationSystemKind, width: usize, height: usize) -> Self {
+        let (row, col) = system.system().spawn_position(piece_type);
+        // Spawn positions are for a standard board, keep them centered
+        let col_offset = (width as i32 - BOARD_WIDTH as i32) / 2;
         // Spawn at top of visible area (row 20-21 in a 24-row board)
         Self {
             piece_type,
             rotation: Rotation::North,
-            row: 20 + row, // Spawn above visible area
-            col,
+            row: height as i32 + row, // Spawn above visible area
+            col: col + col_offset,
             last_kick: 0,
+            last_rotation: None,
+            system,
         }
     }
 
+    /// Get the block offsets for the current rotation
+    pub fn shape(&self) -> [(i32, i32); 4] {
+        self.system.system().shape(self.piece_type, self.rotation)
+    }
+
     /// Get the absolute positions of all 4 blocks
     pub fn block_positions(&self) -> [(i32, i32); 4] {
-        let offsets = self.piece_type.shape(self.rotation);
-        offsets.map(|(dr, dc)| (self.row + dr, self.col + dc))
+        self.shape().map(|(dr, dc)| (self.row + dr, self.col + dc))
     }
 
     /// Try to move left, returns true if successful
@@ -45,6 +71,7 @@ impl Piece {
         self.col -= 1;
         if board.are_positions_valid(&self.block_positions()) {
             self.last_kick = 0; // Reset kick tracking on successful move
+            self.last_rotation = None;
             true
         } else {
             self.col += 1;
@@ -57,6 +84,7 @@ impl Piece {
         self.col += 1;
         if board.are_positions_valid(&self.block_positions()) {
             self.last_kick = 0; // Reset kick tracking on successful move
+            self.last_rotation = None;
             true
         } else {
             self.col -= 1;
@@ -69,6 +97,7 @@ impl Piece {
         self.row -= 1;
         if board.are_positions_valid(&self.block_positions()) {
             self.last_kick = 0; // Reset kick tracking on successful move
+            self.last_rotation = None;
             true
         } else {
             self.row += 1;
@@ -76,14 +105,10 @@ impl Piece {
         }
     }
 
-    /// Try to rotate, using SRS wall kicks
+    /// Try to rotate, using the rotation system's kicks
     pub fn rotate(&mut self, direction: RotationDirection, board: &Board) -> bool {
-        let new_rotation = match direction {
-            RotationDirection::Clockwise => self.rotation.cw(),
-            RotationDirection::CounterClockwise => self.rotation.ccw(),
-        };
-
-        let kicks = get_wall_kicks(self.piece_type, self.rotation, direction);
+        let new_rotation = self.rotation.rotated(direction);
+        let kicks = self.system.system().kicks(self, direction, board);
 
         // Store original position
         let original_row = self.row;
@@ -98,6 +123,7 @@ impl Piece {
 
             if board.are_positions_valid(&self.block_positions()) {
                 self.last_kick = (kick_idx + 1) as u8;
+                self.last_rotation = Some(direction);
                 return true;
             }
         }
@@ -123,7 +149,7 @@ impl Piece {
         let mut ghost_row = self.row;
         loop {
             ghost_row -= 1;
-            let offsets = self.piece_type.shape(self.rotation);
+            let offsets = self.shape();
             let positions: Vec<_> = offsets
                 .iter()
                 .map(|(dr, dc)| (ghost_row + dr, self.col + dc))
@@ -178,4 +204,24 @@ mod tests {
         // I piece should drop to the bottom
         assert!(distance > 0);
     }
+
+    #[test]
+    fn test_spawn_on_custom_board() {
+        let board = Board::with_size(4, 40);
+        let piece = Piece::spawn(TetrominoType::I, RotationSystemKind::Srs, &board);
+        assert_eq!(piece.row, 40);
+        assert!(board.are_positions_valid(&piece.block_positions()));
+        assert!(piece.block_positions().iter().all(|&(_, col)| (0..4).contains(&col)));
+    }
+
+    #[test]
+    fn test_rotate_180() {
+        let board = Board::new();
+        let mut piece = Piece::new(TetrominoType::T);
+        assert!(piece.rotate(RotationDirection::Half, &board));
+        assert_eq!(piece.rotation, Rotation::South);
+        assert_eq!(piece.last_rotation, Some(RotationDirection::Half));
+        assert!(piece.rotate(RotationDirection::Half, &board));
+        assert_eq!(piece.rotation, Rotation::North);
+    }
 }
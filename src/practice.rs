@@ -0,0 +1,70 @@
+//! Practice sandbox: undo, redo and reset placements to drill openers
+//!
+//! The state is snapshotted each time a piece spawns. Locking the piece
+//! makes that snapshot an undo point, so undo puts the piece back as it
+//! spawned with the board, score, hold and queue from before it was placed.
+
+use crate::bag::Bag;
+use crate::board::Board;
+use crate::score::Score;
+use crate::tetromino::TetrominoType;
+
+/// Everything a placement changes, taken as a piece spawns
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub board: Board,
+    pub score: Score,
+    pub hold_piece: Option<TetrominoType>,
+    /// The piece that spawned
+    pub piece: TetrominoType,
+    pub bag: Bag,
+    pub pieces_placed: u32,
+}
+
+/// Undo and redo history
+#[derive(Debug, Clone, Default)]
+pub struct PracticeState {
+    /// State as the current piece spawned
+    pub spawned: Option<Snapshot>,
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+}
+
+impl PracticeState {
+    /// A piece locked: its spawn state becomes an undo point, and anything
+    /// that was undone can't be redone any more
+    pub fn on_lock(&mut self) {
+        if let Some(spawned) = self.spawned.take() {
+            self.undo.push(spawned);
+            self.redo.clear();
+        }
+    }
+
+    /// Step back one placement, returns the state to restore
+    pub fn undo(&mut self) -> Option<Snapshot> {
+        let previous = self.undo.pop()?;
+        self.redo.extend(self.spawned.take());
+        Some(previous)
+    }
+
+    /// Step forward one undone placement, returns the state to restore
+    pub fn redo(&mut self) -> Option<Snapshot> {
+        let next = self.redo.pop()?;
+        self.undo.extend(self.spawned.take());
+        Some(next)
+    }
+
+    /// Back to the first piece with the history cleared, returns the state
+    /// to restore
+    pub fn reset(&mut self) -> Option<Snapshot> {
+        let first = self.undo.first().or(self.spawned.as_ref()).cloned()?;
+        self.undo.clear();
+        self.redo.clear();
+        Some(first)
+    }
+
+    /// Number of placements that can be undone
+    pub fn undo_count(&self) -> usize {
+        self.undo.len()
+    }
+}
@@ -0,0 +1,263 @@
+//! Piece randomizers
+//!
+//! Every randomizer draws from its own ChaCha8 RNG seeded from the game seed,
+//! so the same seed always deals the same pieces (multiplayer and replays).
+
+use crate::tetromino::TetrominoType;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+/// Generates the piece sequence
+pub trait Randomizer: Debug {
+    /// Deal the next piece
+    fn next_piece(&mut self) -> TetrominoType;
+
+    /// Copy of this randomizer in its current state
+    fn clone_box(&self) -> Box<dyn Randomizer>;
+}
+
+impl Clone for Box<dyn Randomizer> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// Selectable randomizers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RandomizerKind {
+    #[default]
+    SevenBag,
+    FourteenBag,
+    Random,
+    Classic,
+    Tgm,
+}
+
+impl RandomizerKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RandomizerKind::SevenBag => "7-Bag",
+            RandomizerKind::FourteenBag => "14-Bag",
+            RandomizerKind::Random => "Random",
+            RandomizerKind::Classic => "Classic",
+            RandomizerKind::Tgm => "TGM",
+        }
+    }
+
+    pub fn all() -> &'static [RandomizerKind] {
+        &[
+            RandomizerKind::SevenBag,
+            RandomizerKind::FourteenBag,
+            RandomizerKind::Random,
+            RandomizerKind::Classic,
+            RandomizerKind::Tgm,
+        ]
+    }
+
+    /// Look up a randomizer by its display name
+    pub fn from_name(name: &str) -> Option<RandomizerKind> {
+        Self::all().iter().copied().find(|kind| kind.name() == name)
+    }
+
+    /// Create a randomizer of this kind from a seed
+    pub fn create(&self, seed: u64) -> Box<dyn Randomizer> {
+        let rng = ChaCha8Rng::seed_from_u64(seed);
+        match self {
+            RandomizerKind::SevenBag => Box::new(BagRandomizer::new(rng, 1)),
+            RandomizerKind::FourteenBag => Box::new(BagRandomizer::new(rng, 2)),
+            RandomizerKind::Random => Box::new(PureRandom { rng }),
+            RandomizerKind::Classic => Box::new(ClassicRandomizer { rng, last: None }),
+            RandomizerKind::Tgm => Box::new(TgmRandomizer::new(rng)),
+        }
+    }
+}
+
+/// Shuffles bags of every piece and deals them out before reshuffling
+/// (one copy of each piece per bag for 7-bag, two for 14-bag)
+#[derive(Debug, Clone)]
+pub struct BagRandomizer {
+    rng: ChaCha8Rng,
+    copies: usize,
+    bag: Vec<TetrominoType>,
+}
+
+impl BagRandomizer {
+    pub fn new(rng: ChaCha8Rng, copies: usize) -> Self {
+        Self {
+            rng,
+            copies,
+            bag: Vec::with_capacity(7 * copies),
+        }
+    }
+}
+
+impl Randomizer for BagRandomizer {
+    fn next_piece(&mut self) -> TetrominoType {
+        if self.bag.is_empty() {
+            for _ in 0..self.copies {
+                self.bag.extend(TetrominoType::all());
+            }
+            self.bag.shuffle(&mut self.rng);
+            // Deal from the end so a bag comes out in shuffled order
+            self.bag.reverse();
+        }
+        self.bag.pop().unwrap_or(TetrominoType::I)
+    }
+
+    fn clone_box(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+/// Every piece equally likely, independent of history
+#[derive(Debug, Clone)]
+pub struct PureRandom {
+    rng: ChaCha8Rng,
+}
+
+impl Randomizer for PureRandom {
+    fn next_piece(&mut self) -> TetrominoType {
+        TetrominoType::all()[self.rng.gen_range(0..7)]
+    }
+
+    fn clone_box(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+/// NES: roll 8 sides, reroll once if it's the 8th side or a repeat
+#[derive(Debug, Clone)]
+pub struct ClassicRandomizer {
+    rng: ChaCha8Rng,
+    last: Option<TetrominoType>,
+}
+
+impl Randomizer for ClassicRandomizer {
+    fn next_piece(&mut self) -> TetrominoType {
+        let roll = self.rng.gen_range(0..8);
+        let piece = match TetrominoType::all().get(roll) {
+            Some(&piece) if Some(piece) != self.last => piece,
+            // The reroll is accepted even if it repeats
+            _ => TetrominoType::all()[self.rng.gen_range(0..7)],
+        };
+        self.last = Some(piece);
+        piece
+    }
+
+    fn clone_box(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+/// TGM: reroll up to 6 times while the piece is in the last 4 dealt
+#[derive(Debug, Clone)]
+pub struct TgmRandomizer {
+    rng: ChaCha8Rng,
+    history: [TetrominoType; 4],
+    first: bool,
+}
+
+impl TgmRandomizer {
+    /// Rerolls after the first draw, so up to 7 draws per piece
+    const REROLLS: usize = 6;
+
+    pub fn new(rng: ChaCha8Rng) -> Self {
+        use TetrominoType::*;
+        Self {
+            rng,
+            history: [Z, S, S, Z],
+            first: true,
+        }
+    }
+
+    /// Draw, rerolling while the piece is in the history, keeps the last draw
+    fn roll(history: &[TetrominoType; 4], mut draw: impl FnMut() -> TetrominoType) -> TetrominoType {
+        let mut piece = draw();
+        for _ in 0..Self::REROLLS {
+            if !history.contains(&piece) {
+                break;
+            }
+            piece = draw();
+        }
+        piece
+    }
+}
+
+impl Randomizer for TgmRandomizer {
+    fn next_piece(&mut self) -> TetrominoType {
+        use TetrominoType::*;
+
+        let piece = if self.first {
+            // First piece is never S, Z or O
+            self.first = false;
+            [I, J, L, T][self.rng.gen_range(0..4)]
+        } else {
+            let rng = &mut self.rng;
+            Self::roll(&self.history, || TetrominoType::all()[rng.gen_range(0..7)])
+        };
+
+        self.history.rotate_left(1);
+        self.history[3] = piece;
+        piece
+    }
+
+    fn clone_box(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn deal(kind: RandomizerKind, seed: u64, count: usize) -> Vec<TetrominoType> {
+        let mut randomizer = kind.create(seed);
+        (0..count).map(|_| randomizer.next_piece()).collect()
+    }
+
+    #[test]
+    fn test_same_seed_same_sequence() {
+        for &kind in RandomizerKind::all() {
+            assert_eq!(deal(kind, 9, 100), deal(kind, 9, 100), "{}", kind.name());
+        }
+    }
+
+    #[test]
+    fn test_fourteen_bag_has_two_of_each() {
+        let pieces = deal(RandomizerKind::FourteenBag, 3, 14);
+        for piece_type in TetrominoType::all() {
+            assert_eq!(pieces.iter().filter(|&&p| p == piece_type).count(), 2);
+        }
+    }
+
+    #[test]
+    fn test_tgm_first_piece() {
+        for seed in 0..50 {
+            let first = deal(RandomizerKind::Tgm, seed, 1)[0];
+            assert!(!matches!(first, TetrominoType::S | TetrominoType::Z | TetrominoType::O));
+        }
+    }
+
+    #[test]
+    fn test_tgm_rerolls() {
+        use TetrominoType::*;
+        let history = [Z, S, S, Z];
+
+        // Six rerolls: the seventh draw is used even if it's in the history
+        let mut draws = [Z, S, Z, S, Z, S, T].into_iter();
+        assert_eq!(TgmRandomizer::roll(&history, || draws.next().unwrap()), T);
+        let mut draws = [Z, S, Z, S, Z, S, Z, T].into_iter();
+        assert_eq!(TgmRandomizer::roll(&history, || draws.next().unwrap()), Z);
+        assert_eq!(draws.next(), Some(T));
+    }
+
+    #[test]
+    fn test_random_deals_everything() {
+        let pieces: HashSet<_> = deal(RandomizerKind::Random, 1, 200).into_iter().collect();
+        assert_eq!(pieces.len(), 7);
+    }
+}
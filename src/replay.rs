@@ -0,0 +1,523 @@
+//! Replay recording and persistence
+//!
+//! The engine is deterministic for a given seed, so a replay only stores the
+//! seed, mode, a gameplay settings snapshot and the inputs with the tick they
+//! happened on.
+//! Replays are written as JSON to the `replays` folder in the config dir.
+//! The personal best of each Sprint distance and of Ultra is also kept in
+//! `replays/pb`, to race against as a ghost.
+
+use crate::custom::CustomMode;
+use crate::game::{duration_to_frames, Action, Game, GameState, HeldInput, TICK, TICK_RATE};
+use crate::mode::GameMode;
+use crate::rules::Rules;
+use crate::settings::{GameplaySettings, Settings};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Current replay format version (bump when the format changes)
+pub const REPLAY_VERSION: u32 = 4;
+
+/// Playback speeds available in the replay viewer
+pub const PLAYBACK_SPEEDS: [f32; 5] = [0.25, 0.5, 1.0, 2.0, 4.0];
+
+/// A single recorded input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReplayInput {
+    /// Player action passed to `Game::process_action`
+    Action(Action),
+    /// Garbage received from an opponent (Versus)
+    Garbage(u8),
+    /// Rotate and hold buttons held down changed, passed to `Game::set_held`
+    Held(HeldInput),
+}
+
+/// An input with the tick it happened on, counted from the start of play
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayEvent {
+    pub frame: u64,
+    pub input: ReplayInput,
+}
+
+/// A recorded game
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    /// Format version header
+    pub version: u32,
+    /// Seed passed to `Game::with_seed`
+    pub seed: u64,
+    pub mode: GameMode,
+    /// Rules the game was played with
+    #[serde(default)]
+    pub rules: Rules,
+    /// Definition of the custom mode played (Custom only)
+    #[serde(default)]
+    pub custom: Option<CustomMode>,
+    /// Gameplay settings when the game was played
+    pub gameplay: GameplaySettings,
+    /// Date as ISO string
+    pub date: String,
+    /// Final result (for listing replays)
+    pub score: u64,
+    pub lines: u32,
+    pub time_ms: u64,
+    /// Recorded inputs in order
+    pub events: Vec<ReplayEvent>,
+}
+
+impl Replay {
+    /// Build a replay from a finished game
+    pub fn from_game(game: &Game, settings: &Settings) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            seed: game.seed(),
+            mode: game.mode(),
+            rules: game.rules().clone(),
+            custom: game.mode_state.custom.clone(),
+            gameplay: settings.gameplay.clone(),
+            date: crate::settings::chrono_lite_now(),
+            score: game.score.points,
+            lines: game.score.lines,
+            time_ms: game.mode_state.elapsed.as_millis() as u64,
+            events: game.recorded_events().to_vec(),
+        }
+    }
+
+    /// Get the replays directory path
+    pub fn replays_dir() -> Option<PathBuf> {
+        Settings::config_dir().map(|dir| dir.join("replays"))
+    }
+
+    /// Save to the replays directory, returns the file path
+    pub fn save(&self) -> Result<PathBuf, String> {
+        let Some(dir) = Self::replays_dir() else {
+            return Err("Could not determine replays dir".to_string());
+        };
+
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create replays dir: {}", e))?;
+
+        let millis = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let path = dir.join(self.file_name(millis));
+
+        let contents =
+            serde_json::to_string(self).map_err(|e| format!("Failed to serialize: {}", e))?;
+        fs::write(&path, contents).map_err(|e| format!("Failed to write replay: {}", e))?;
+
+        Ok(path)
+    }
+
+    /// File name for a replay saved at `millis` since the epoch, e.g.
+    /// "1760659200123-marathon-000000000000002a.json". The timestamp sorts
+    /// replays chronologically and the seed keeps games saved in the same
+    /// millisecond apart. Custom games are named after their custom mode.
+    fn file_name(&self, millis: u128) -> String {
+        let name = self.custom.as_ref().map_or(self.mode.name(), |custom| custom.name.as_str());
+        let mode: String = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+            .collect();
+        format!("{}-{}-{:016x}.json", millis, mode, self.seed)
+    }
+
+    /// Name of a mode's personal best replay file (None if the mode has no
+    /// ghost race)
+    fn pb_name(mode: GameMode) -> Option<String> {
+        match mode {
+            GameMode::Sprint(distance) => Some(format!("sprint-{}.json", distance)),
+            GameMode::Ultra => Some("ultra.json".to_string()),
+            _ => None,
+        }
+    }
+
+    /// Save as the personal best for its mode, replacing the previous one
+    pub fn save_pb(&self) -> Result<PathBuf, String> {
+        let Some(dir) = Self::replays_dir().map(|dir| dir.join("pb")) else {
+            return Err("Could not determine replays dir".to_string());
+        };
+        let Some(name) = Self::pb_name(self.mode) else {
+            return Err(format!("{} has no personal best replay", self.mode.name()));
+        };
+
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create replays dir: {}", e))?;
+        let path = dir.join(name);
+        let contents =
+            serde_json::to_string(self).map_err(|e| format!("Failed to serialize: {}", e))?;
+        fs::write(&path, contents).map_err(|e| format!("Failed to write replay: {}", e))?;
+
+        Ok(path)
+    }
+
+    /// Load the personal best replay of a mode, if one was saved
+    pub fn load_pb(mode: GameMode) -> Option<Replay> {
+        let path = Self::replays_dir()?.join("pb").join(Self::pb_name(mode)?);
+        Self::load(&path).ok()
+    }
+
+    /// List saved replay files, newest first. Files are only read when one
+    /// is opened.
+    pub fn list() -> Vec<ReplayFile> {
+        let Some(dir) = Self::replays_dir() else {
+            return Vec::new();
+        };
+        let Ok(entries) = fs::read_dir(&dir) else {
+            return Vec::new();
+        };
+
+        let mut files: Vec<ReplayFile> = entries
+            .filter_map(|entry| entry.ok().and_then(|e| ReplayFile::from_path(e.path())))
+            .collect();
+        files.sort_by_key(|file| std::cmp::Reverse(file.saved_ms));
+        files
+    }
+
+    /// Load a replay file, accepting any version up to the current one
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents =
+            fs::read_to_string(path).map_err(|e| format!("Failed to read replay: {}", e))?;
+        let value: serde_json::Value =
+            serde_json::from_str(&contents).map_err(|e| format!("Invalid replay: {}", e))?;
+
+        let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+        let value = match version {
+            1 => migrate_v2(migrate_v1(value)),
+            2 | 3 => migrate_v2(value),
+            REPLAY_VERSION => value,
+            v => return Err(format!("Unsupported replay version {}", v)),
+        };
+        serde_json::from_value(value).map_err(|e| format!("Invalid replay: {}", e))
+    }
+}
+
+/// Convert a v1 replay (events stamped in milliseconds) to the current format
+fn migrate_v1(mut value: serde_json::Value) -> serde_json::Value {
+    if let Some(events) = value.get_mut("events").and_then(|v| v.as_array_mut()) {
+        for event in events {
+            let Some(event) = event.as_object_mut() else {
+                continue;
+            };
+            let time_ms = event.remove("time_ms").and_then(|v| v.as_u64()).unwrap_or(0);
+            let frame = (time_ms * TICK_RATE + 500) / 1000;
+            event.insert("frame".to_string(), frame.into());
+        }
+    }
+    value["version"] = 2.into();
+    value
+}
+
+/// Convert a v2 or v3 replay (Sprint was always 40 lines) to the current format
+fn migrate_v2(mut value: serde_json::Value) -> serde_json::Value {
+    if value["mode"] == "Sprint" {
+        value["mode"] = serde_json::json!({ "Sprint": 40 });
+    }
+    value["version"] = REPLAY_VERSION.into();
+    value
+}
+
+/// Plays a replay back by feeding its recorded inputs into a fresh `Game`
+pub struct ReplayPlayer {
+    pub replay: Replay,
+    pub game: Game,
+    /// Index of the next event to feed
+    next_event: usize,
+    /// Whether playback is paused
+    pub paused: bool,
+    /// Index into `PLAYBACK_SPEEDS`
+    speed_index: usize,
+    /// Simulated time owed to the game but not yet stepped
+    pending: Duration,
+    /// Total pieces placed over the whole replay
+    pub total_pieces: u32,
+    /// Piece number typed by the viewer for seeking
+    pub seek_input: String,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Self {
+        let mut player = Self {
+            game: Self::fresh_game(&replay),
+            replay,
+            next_event: 0,
+            paused: false,
+            speed_index: 2, // 1x
+            pending: Duration::ZERO,
+            total_pieces: 0,
+            seek_input: String::new(),
+        };
+
+        // Simulate the whole replay once to learn its length
+        player.run_until(|_| false);
+        player.total_pieces = player.game.pieces_placed;
+        player.restart();
+        player
+    }
+
+    /// Create the game the replay was recorded from
+    fn fresh_game(replay: &Replay) -> Game {
+        let mut game = match &replay.custom {
+            Some(custom) => Game::with_custom(custom, replay.seed, replay.rules.clone()),
+            None => Game::with_rules(replay.mode, replay.seed, replay.rules.clone()),
+        };
+        game.garbage_queue
+            .set_delay(Duration::from_millis(replay.gameplay.garbage_delay_ms));
+        game
+    }
+
+    /// Go back to the start of the replay
+    fn restart(&mut self) {
+        self.game = Self::fresh_game(&self.replay);
+        self.next_event = 0;
+        self.pending = Duration::ZERO;
+    }
+
+    /// Current playback speed multiplier
+    pub fn speed(&self) -> f32 {
+        PLAYBACK_SPEEDS[self.speed_index]
+    }
+
+    pub fn faster(&mut self) {
+        self.speed_index = (self.speed_index + 1).min(PLAYBACK_SPEEDS.len() - 1);
+    }
+
+    pub fn slower(&mut self) {
+        self.speed_index = self.speed_index.saturating_sub(1);
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    /// Number of pieces placed so far
+    pub fn current_piece(&self) -> u32 {
+        self.game.pieces_placed
+    }
+
+    /// Ticks from the start of play to the end of the recording
+    fn end_frame(&self) -> u64 {
+        let last_event = self.replay.events.last().map_or(0, |event| event.frame);
+        last_event.max(duration_to_frames(Duration::from_millis(self.replay.time_ms)))
+    }
+
+    /// Whether playback reached the end of the recording
+    pub fn is_finished(&self) -> bool {
+        if matches!(self.game.state, GameState::GameOver | GameState::Victory) {
+            return true;
+        }
+        let Some(frame) = self.game.play_frames() else {
+            return false;
+        };
+        self.next_event >= self.replay.events.len() && frame >= self.end_frame()
+    }
+
+    /// Advance playback by `dt` of real time, scaled by the playback speed
+    pub fn update(&mut self, dt: Duration) {
+        if self.paused || self.is_finished() {
+            return;
+        }
+
+        self.pending += dt.mul_f32(self.speed());
+        while self.pending >= TICK && !self.is_finished() {
+            self.pending -= TICK;
+            self.step();
+        }
+    }
+
+    /// Step forward until the next piece locks
+    pub fn step_piece_forward(&mut self) {
+        let target = self.game.pieces_placed + 1;
+        self.run_until(|game| game.pieces_placed >= target);
+    }
+
+    /// Step back one piece (replays from the start)
+    pub fn step_piece_back(&mut self) {
+        self.seek_to_piece(self.game.pieces_placed.saturating_sub(1));
+    }
+
+    /// Jump to the moment the given number of pieces have been placed
+    pub fn seek_to_piece(&mut self, piece: u32) {
+        // The engine is deterministic, so seeking is replaying from the start
+        self.restart();
+        self.run_until(|game| game.pieces_placed >= piece);
+    }
+
+    /// Ghost race: play on until the replay has run as many ticks as the
+    /// live game
+    pub fn catch_up(&mut self, frames: u64) {
+        self.run_until(|game| game.play_frames().is_some_and(|played| played >= frames));
+    }
+
+    /// Step the simulation until the condition holds or the replay ends
+    fn run_until(&mut self, done: impl Fn(&Game) -> bool) {
+        while !self.is_finished() && !done(&self.game) {
+            self.step();
+        }
+    }
+
+    /// Advance the game by one tick, feeding inputs that are due
+    fn step(&mut self) {
+        self.game.tick();
+
+        if let Some(frame) = self.game.play_frames() {
+            while let Some(event) = self.replay.events.get(self.next_event) {
+                if event.frame > frame {
+                    break;
+                }
+                match event.input {
+                    ReplayInput::Action(action) => self.game.process_action(action),
+                    ReplayInput::Garbage(lines) => self.game.queue_garbage(lines),
+                    ReplayInput::Held(held) => self.game.set_held(held),
+                }
+                self.next_event += 1;
+            }
+        }
+
+        // Nothing consumes lock notifications during playback
+        self.game.piece_just_locked = false;
+    }
+}
+
+/// A saved replay, described by its file name
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayFile {
+    pub path: PathBuf,
+    /// Milliseconds since the epoch when the replay was saved
+    pub saved_ms: u128,
+    /// Slugged mode name, e.g. "cheese-race"
+    pub mode: String,
+}
+
+impl ReplayFile {
+    /// Describe a replay from a name written by `Replay::file_name`
+    fn from_path(path: PathBuf) -> Option<Self> {
+        if path.extension()? != "json" {
+            return None;
+        }
+        let stem = path.file_stem()?.to_str()?;
+        let (millis, rest) = stem.split_once('-')?;
+        let saved_ms = millis.parse().ok()?;
+        let mode = match rest.rsplit_once('-') {
+            Some((mode, seed)) if seed.len() == 16 && u64::from_str_radix(seed, 16).is_ok() => mode,
+            _ => rest,
+        };
+        Some(Self { mode: mode.to_string(), saved_ms, path })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let game = Game::with_seed(GameMode::Sprint(40), 42);
+        let mut replay = Replay::from_game(&game, &Settings::default());
+        replay.events.push(ReplayEvent {
+            frame: 90,
+            input: ReplayInput::Action(Action::HardDrop),
+        });
+
+        let path = std::env::temp_dir().join("tetrs-replay-test.json");
+        fs::write(&path, serde_json::to_string(&replay).unwrap()).unwrap();
+        let loaded = Replay::load(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(loaded.version, REPLAY_VERSION);
+        assert_eq!(loaded.seed, 42);
+        assert_eq!(loaded.mode, GameMode::Sprint(40));
+        assert_eq!(loaded.events, replay.events);
+    }
+
+    #[test]
+    fn test_file_name() {
+        let replay = Replay::from_game(&Game::with_seed(GameMode::Marathon, 42), &Settings::default());
+        assert_eq!(replay.file_name(1_760_659_200_123), "1760659200123-marathon-000000000000002a.json");
+
+        let file = ReplayFile::from_path(PathBuf::from(replay.file_name(1_760_659_200_123))).unwrap();
+        assert_eq!(file.saved_ms, 1_760_659_200_123);
+        assert_eq!(file.mode, "marathon");
+        assert!(ReplayFile::from_path(PathBuf::from("notes.txt")).is_none());
+    }
+
+    #[test]
+    fn test_playback_seek() {
+        let game = Game::with_seed(GameMode::Marathon, 7);
+        let mut replay = Replay::from_game(&game, &Settings::default());
+        // Hard drop a piece every second, then quit
+        for i in 1..=5 {
+            replay.events.push(ReplayEvent {
+                frame: i * TICK_RATE,
+                input: ReplayInput::Action(Action::HardDrop),
+            });
+        }
+        replay.events.push(ReplayEvent {
+            frame: 6 * TICK_RATE,
+            input: ReplayInput::Action(Action::Quit),
+        });
+
+        let mut player = ReplayPlayer::new(replay);
+        assert_eq!(player.total_pieces, 5);
+
+        player.seek_to_piece(3);
+        assert_eq!(player.current_piece(), 3);
+        let board_at_three = player.game.board.clone();
+
+        player.step_piece_forward();
+        assert_eq!(player.current_piece(), 4);
+        player.step_piece_back();
+        assert_eq!(player.current_piece(), 3);
+
+        // Deterministic: seeking back lands on the same board
+        for row in 0..crate::board::TOTAL_HEIGHT as i32 {
+            for col in 0..crate::board::BOARD_WIDTH as i32 {
+                assert_eq!(player.game.board.get(row, col), board_at_three.get(row, col));
+            }
+        }
+    }
+
+    #[test]
+    fn test_ghost_catch_up() {
+        let game = Game::with_seed(GameMode::Sprint(40), 5);
+        let mut replay = Replay::from_game(&game, &Settings::default());
+        for i in 1..=3 {
+            replay.events.push(ReplayEvent {
+                frame: i * TICK_RATE,
+                input: ReplayInput::Action(Action::HardDrop),
+            });
+        }
+
+        let mut ghost = ReplayPlayer::new(replay);
+        ghost.catch_up(2 * TICK_RATE);
+        assert_eq!(ghost.game.play_frames(), Some(2 * TICK_RATE));
+        assert_eq!(ghost.current_piece(), 2);
+        // Never runs backwards
+        ghost.catch_up(TICK_RATE);
+        assert_eq!(ghost.current_piece(), 2);
+    }
+
+    #[test]
+    fn test_load_v1() {
+        let mut replay = Replay::from_game(&Game::with_seed(GameMode::Sprint(40), 3), &Settings::default());
+        replay.events.push(ReplayEvent {
+            frame: 0,
+            input: ReplayInput::Action(Action::HardDrop),
+        });
+        let mut value = serde_json::to_value(&replay).unwrap();
+        value["version"] = 1.into();
+        value["mode"] = "Sprint".into();
+        value["events"][0] = serde_json::json!({ "time_ms": 1500, "input": { "Action": "HardDrop" } });
+
+        let path = std::env::temp_dir().join("tetrs-replay-v1-test.json");
+        fs::write(&path, value.to_string()).unwrap();
+        let loaded = Replay::load(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(loaded.version, REPLAY_VERSION);
+        assert_eq!(loaded.mode, GameMode::Sprint(40));
+        assert_eq!(loaded.events[0].frame, 90);
+        assert_eq!(loaded.events[0].input, ReplayInput::Action(Action::HardDrop));
+    }
+}
@@ -0,0 +1,262 @@
+//! Rotation systems: piece shapes, spawn positions and kicks
+//!
+//! SRS is the modern default. ARS (Arika, the TGM series) uses bottom-aligned
+//! shapes and simple one-column kicks. Classic is the NES system: true
+//! rotation about the center and no kicks at all.
+
+use crate::board::Board;
+use crate::piece::Piece;
+use crate::srs::get_wall_kicks;
+use crate::tetromino::{Rotation, RotationDirection, TetrominoType};
+use serde::{Deserialize, Serialize};
+
+/// Defines how pieces look, where they spawn and how they rotate
+pub trait RotationSystem {
+    /// Block offsets (row, col) from the pivot, row increases upward
+    fn shape(&self, piece_type: TetrominoType, rotation: Rotation) -> [(i32, i32); 4];
+
+    /// Spawn pivot (row, col), row relative to the top of the visible board
+    fn spawn_position(&self, piece_type: TetrominoType) -> (i32, i32);
+
+    /// Offsets (row, col) to try in order when rotating the piece
+    fn kicks(&self, piece: &Piece, direction: RotationDirection, board: &Board) -> Vec<(i32, i32)>;
+
+    /// Whether T-spins are recognized (using SRS corner rules)
+    fn t_spins(&self) -> bool {
+        false
+    }
+}
+
+/// Selectable rotation systems
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RotationSystemKind {
+    #[default]
+    Srs,
+    Ars,
+    Classic,
+}
+
+impl RotationSystemKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RotationSystemKind::Srs => "SRS",
+            RotationSystemKind::Ars => "ARS",
+            RotationSystemKind::Classic => "Classic",
+        }
+    }
+
+    pub fn all() -> &'static [RotationSystemKind] {
+        &[RotationSystemKind::Srs, RotationSystemKind::Ars, RotationSystemKind::Classic]
+    }
+
+    /// Look up a rotation system by its display name
+    pub fn from_name(name: &str) -> Option<RotationSystemKind> {
+        Self::all().iter().copied().find(|kind| kind.name() == name)
+    }
+
+    /// Get the rotation system implementation
+    pub fn system(&self) -> &'static dyn RotationSystem {
+        match self {
+            RotationSystemKind::Srs => &Srs,
+            RotationSystemKind::Ars => &Ars,
+            RotationSystemKind::Classic => &Classic,
+        }
+    }
+}
+
+/// Super Rotation System (guideline)
+pub struct Srs;
+
+impl RotationSystem for Srs {
+    fn shape(&self, piece_type: TetrominoType, rotation: Rotation) -> [(i32, i32); 4] {
+        piece_type.shape(rotation)
+    }
+
+    fn spawn_position(&self, piece_type: TetrominoType) -> (i32, i32) {
+        piece_type.spawn_position()
+    }
+
+    fn kicks(&self, piece: &Piece, direction: RotationDirection, _board: &Board) -> Vec<(i32, i32)> {
+        get_wall_kicks(piece.piece_type, piece.rotation, direction).to_vec()
+    }
+
+    fn t_spins(&self) -> bool {
+        true
+    }
+}
+
+/// Arika Rotation System (TGM)
+pub struct Ars;
+
+impl RotationSystem for Ars {
+    fn shape(&self, piece_type: TetrominoType, rotation: Rotation) -> [(i32, i32); 4] {
+        use Rotation::*;
+
+        // Pieces sit at the bottom of their 3x3 box, T/J/L spawn flat side up
+        match piece_type {
+            TetrominoType::I => match rotation {
+                North | South => [(0, -1), (0, 0), (0, 1), (0, 2)],
+                East | West => [(1, 1), (0, 1), (-1, 1), (-2, 1)],
+            },
+            TetrominoType::O => [(0, 0), (0, 1), (-1, 0), (-1, 1)],
+            TetrominoType::T => match rotation {
+                North => [(0, -1), (0, 0), (0, 1), (-1, 0)],
+                East => [(1, 0), (0, -1), (0, 0), (-1, 0)],
+                South => [(0, 0), (-1, -1), (-1, 0), (-1, 1)],
+                West => [(1, 0), (0, 0), (0, 1), (-1, 0)],
+            },
+            TetrominoType::S => match rotation {
+                North | South => [(0, 0), (0, 1), (-1, -1), (-1, 0)],
+                East | West => [(1, -1), (0, -1), (0, 0), (-1, 0)],
+            },
+            TetrominoType::Z => match rotation {
+                North | South => [(0, -1), (0, 0), (-1, 0), (-1, 1)],
+                East | West => [(1, 1), (0, 0), (0, 1), (-1, 0)],
+            },
+            TetrominoType::J => match rotation {
+                North => [(0, -1), (0, 0), (0, 1), (-1, 1)],
+                East => [(1, 0), (0, 0), (-1, 0), (-1, -1)],
+                South => [(0, -1), (-1, -1), (-1, 0), (-1, 1)],
+                West => [(1, 0), (1, 1), (0, 0), (-1, 0)],
+            },
+            TetrominoType::L => match rotation {
+                North => [(0, -1), (0, 0), (0, 1), (-1, -1)],
+                East => [(1, -1), (1, 0), (0, 0), (-1, 0)],
+                South => [(0, 1), (-1, -1), (-1, 0), (-1, 1)],
+                West => [(1, 0), (0, 0), (-1, 0), (-1, 1)],
+            },
+        }
+    }
+
+    fn spawn_position(&self, piece_type: TetrominoType) -> (i32, i32) {
+        match piece_type {
+            TetrominoType::I | TetrominoType::O => (0, 4),
+            _ => (1, 4),
+        }
+    }
+
+    fn kicks(&self, piece: &Piece, direction: RotationDirection, board: &Board) -> Vec<(i32, i32)> {
+        match piece.piece_type {
+            // I and O never kick
+            TetrominoType::I | TetrominoType::O => vec![(0, 0)],
+            TetrominoType::T | TetrominoType::J | TetrominoType::L
+                if self.center_column_blocked(piece, direction, board) =>
+            {
+                vec![(0, 0)]
+            }
+            // Otherwise try one step right, then one step left
+            _ => vec![(0, 0), (0, 1), (0, -1)],
+        }
+    }
+}
+
+impl Ars {
+    /// Center column rule: T/J/L don't kick when the first blocked cell of
+    /// the rotated piece (in reading order) is in the center column
+    fn center_column_blocked(&self, piece: &Piece, direction: RotationDirection, board: &Board) -> bool {
+        let mut cells = self.shape(piece.piece_type, piece.rotation.rotated(direction));
+        // Reading order: top row first, then left to right
+        cells.sort_by_key(|&(row, col)| (-row, col));
+        cells
+            .iter()
+            .find(|&&(row, col)| !board.is_valid_position(piece.row + row, piece.col + col))
+            .is_some_and(|&(_, col)| col == 0)
+    }
+}
+
+/// Classic rotation (NES): true rotation about the center, no kicks
+pub struct Classic;
+
+impl RotationSystem for Classic {
+    fn shape(&self, piece_type: TetrominoType, rotation: Rotation) -> [(i32, i32); 4] {
+        use Rotation::*;
+
+        match piece_type {
+            // Two-state pieces
+            TetrominoType::I => match rotation {
+                North | South => [(0, -2), (0, -1), (0, 0), (0, 1)],
+                East | West => [(2, 0), (1, 0), (0, 0), (-1, 0)],
+            },
+            TetrominoType::S => match rotation {
+                North | South => [(0, 0), (0, 1), (-1, -1), (-1, 0)],
+                East | West => [(1, 0), (0, 0), (0, 1), (-1, 1)],
+            },
+            TetrominoType::Z => match rotation {
+                North | South => [(0, -1), (0, 0), (-1, 0), (-1, 1)],
+                East | West => [(1, 1), (0, 0), (0, 1), (-1, 0)],
+            },
+            TetrominoType::O => piece_type.shape(North),
+            // T/J/L rotate like SRS but spawn flat side up
+            _ => piece_type.shape(rotation.flip()),
+        }
+    }
+
+    fn spawn_position(&self, piece_type: TetrominoType) -> (i32, i32) {
+        match piece_type {
+            TetrominoType::I => (0, 5),
+            TetrominoType::O => (0, 4),
+            _ => (1, 4),
+        }
+    }
+
+    fn kicks(&self, _piece: &Piece, _direction: RotationDirection, _board: &Board) -> Vec<(i32, i32)> {
+        vec![(0, 0)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Cell;
+
+    const ROTATIONS: [Rotation; 4] = [Rotation::North, Rotation::East, Rotation::South, Rotation::West];
+
+    #[test]
+    fn test_shapes_have_four_distinct_blocks() {
+        for kind in RotationSystemKind::all() {
+            for piece_type in TetrominoType::all() {
+                for rotation in ROTATIONS {
+                    let mut shape = kind.system().shape(piece_type, rotation).to_vec();
+                    shape.sort();
+                    shape.dedup();
+                    assert_eq!(shape.len(), 4, "{} {:?} {:?}", kind.name(), piece_type, rotation);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_ars_kicks_off_wall() {
+        let board = Board::new();
+        // Vertical T against the left wall can't rotate in place
+        let mut piece = Piece::with_system(TetrominoType::T, RotationSystemKind::Ars);
+        piece.rotation = Rotation::West;
+        piece.col = 0;
+        piece.row = 5;
+        assert!(piece.rotate(RotationDirection::Clockwise, &board));
+        assert_eq!(piece.col, 1);
+    }
+
+    #[test]
+    fn test_ars_center_column_rule() {
+        let mut board = Board::new();
+        let mut piece = Piece::with_system(TetrominoType::T, RotationSystemKind::Ars);
+        piece.row = 5;
+        piece.col = 4;
+        // Block above the center of a flat T stops the rotation to vertical
+        board.set(6, 4, Cell::Garbage);
+        assert!(!piece.rotate(RotationDirection::Clockwise, &board));
+        assert_eq!(piece.rotation, Rotation::North);
+    }
+
+    #[test]
+    fn test_classic_never_kicks() {
+        let board = Board::new();
+        let mut piece = Piece::with_system(TetrominoType::T, RotationSystemKind::Classic);
+        piece.rotation = Rotation::West;
+        piece.col = 0;
+        piece.row = 5;
+        assert!(!piece.rotate(RotationDirection::Clockwise, &board));
+        assert_eq!(piece.col, 0);
+    }
+}
@@ -0,0 +1,120 @@
+//! Rules a game is played with
+//!
+//! Each mode defines default rules (see `GameMode::rules`), and players can
+//! override some of them in gameplay settings.
+
+use crate::board::{BOARD_HEIGHT, BOARD_WIDTH};
+use crate::gravity::GravityCurve;
+use crate::randomizer::RandomizerKind;
+use crate::rotation::RotationSystemKind;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// What resets the lock delay timer of a grounded piece
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LockReset {
+    /// Only stepping down to a new lowest row resets it (TGM, classic)
+    Step,
+    /// Moves and rotations reset it, up to the reset limit (guideline)
+    #[default]
+    Move,
+    /// Moves and rotations always reset it
+    Infinity,
+}
+
+impl LockReset {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LockReset::Step => "Step",
+            LockReset::Move => "Move",
+            LockReset::Infinity => "Infinity",
+        }
+    }
+
+    pub fn all() -> &'static [LockReset] {
+        &[LockReset::Step, LockReset::Move, LockReset::Infinity]
+    }
+
+    /// Look up a lock reset behavior by its display name
+    pub fn from_name(name: &str) -> Option<LockReset> {
+        Self::all().iter().copied().find(|reset| reset.name() == name)
+    }
+}
+
+/// Gameplay rules for a single game
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rules {
+    /// Piece shapes, spawn positions and kicks
+    pub rotation_system: RotationSystemKind,
+    /// How the piece sequence is generated
+    pub randomizer: RandomizerKind,
+    /// Board columns
+    pub board_width: usize,
+    /// Visible board rows
+    pub board_height: usize,
+    /// Gravity by level
+    pub gravity: GravityCurve,
+    /// Lines to clear for each level up
+    pub lines_per_level: u32,
+    /// Time a grounded piece waits before locking
+    pub lock_delay_ms: u64,
+    /// What resets the lock delay
+    pub lock_reset: LockReset,
+    /// Lock resets allowed per piece with `LockReset::Move`
+    pub lock_reset_limit: u32,
+    /// Entry delay (ARE) between a piece locking and the next spawning
+    pub are_ms: u64,
+    /// Extra entry delay after a lock that clears lines
+    pub line_clear_delay_ms: u64,
+    /// Initial rotation: rotating during the entry delay rotates the next piece
+    pub irs: bool,
+    /// Initial hold: holding during the entry delay holds the next piece
+    pub ihs: bool,
+    /// Cheese Race: percent chance each garbage row's hole moves from the last
+    pub cheese_messiness: u32,
+    /// Number of next pieces shown
+    pub preview_count: usize,
+    /// Whether the hold piece is available
+    pub hold: bool,
+    /// Whether the ghost piece may be shown
+    pub ghost: bool,
+}
+
+impl Rules {
+    pub fn lock_delay(&self) -> Duration {
+        Duration::from_millis(self.lock_delay_ms)
+    }
+
+    pub fn are(&self) -> Duration {
+        Duration::from_millis(self.are_ms)
+    }
+
+    pub fn line_clear_delay(&self) -> Duration {
+        Duration::from_millis(self.line_clear_delay_ms)
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            rotation_system: RotationSystemKind::default(),
+            randomizer: RandomizerKind::default(),
+            board_width: BOARD_WIDTH,
+            board_height: BOARD_HEIGHT,
+            gravity: GravityCurve::Guideline,
+            lines_per_level: 10,
+            lock_delay_ms: 500,
+            lock_reset: LockReset::Move,
+            lock_reset_limit: 15,
+            are_ms: 0,
+            line_clear_delay_ms: 0,
+            irs: true,
+            ihs: true,
+            cheese_messiness: 100,
+            preview_count: 5,
+            hold: true,
+            ghost: true,
+        }
+    }
+}
@@ -24,6 +24,10 @@ pub struct Score {
     pub combo: i32,
     /// Whether last clear was a "difficult" clear (quad or t-spin)
     pub back_to_back: bool,
+    /// Lines to clear for each level up
+    pub lines_per_level: u32,
+    /// Level before any lines are cleared
+    pub starting_level: u32,
 }
 
 impl Score {
@@ -34,9 +38,17 @@ impl Score {
             lines: 0,
             combo: -1,
             back_to_back: false,
+            lines_per_level: 10,
+            starting_level: 1,
         }
     }
 
+    /// Start at `level`, counting level ups from there
+    pub fn set_starting_level(&mut self, level: u32) {
+        self.starting_level = level.max(1);
+        self.level = self.starting_level;
+    }
+
     /// Calculate and add score for a line clear
     /// Returns the action name for display
     pub fn add_clear(&mut self, clear_type: ClearType, all_clear: bool) -> String {
@@ -57,8 +69,8 @@ impl Score {
         // Update lines cleared
         self.lines += lines as u32;
 
-        // Update level (every 10 lines)
-        self.level = (self.lines / 10) + 1;
+        // Update level (every 10 lines by default)
+        self.level = self.starting_level.max(1) + self.lines / self.lines_per_level.max(1);
 
         // Calculate score with multipliers
         let mut score = base_score * self.level as u64;
@@ -124,14 +136,6 @@ impl Score {
     pub fn reset_combo(&mut self) {
         self.combo = -1;
     }
-
-    /// Get the fall speed in seconds for the current level
-    pub fn fall_speed(&self) -> f64 {
-        // Tetris Guideline gravity formula
-        // Level 1: 1 second per row, Level 20: ~0.01 seconds per row
-        let level = self.level.min(20) as f64;
-        (0.8 - ((level - 1.0) * 0.007)).powf(level - 1.0)
-    }
 }
 
 #[cfg(test)]
@@ -183,4 +187,26 @@ mod tests {
         }
         assert_eq!(score.level, 2);
     }
+
+    #[test]
+    fn test_faster_level_up() {
+        let mut score = Score::new();
+        score.lines_per_level = 5;
+        score.add_clear(ClearType::Regular(4), false);
+        score.add_clear(ClearType::Regular(2), false);
+        assert_eq!(score.level, 2);
+    }
+
+    #[test]
+    fn test_starting_level_kept() {
+        let mut score = Score::new();
+        score.set_starting_level(7);
+        score.add_clear(ClearType::Regular(1), false);
+        assert_eq!(score.level, 7);
+        assert_eq!(score.points, 700);
+        score.add_clear(ClearType::Regular(4), false);
+        score.add_clear(ClearType::Regular(4), false);
+        score.add_clear(ClearType::Regular(1), false);
+        assert_eq!(score.level, 8);
+    }
 }
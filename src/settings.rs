@@ -2,8 +2,15 @@
 //!
 //! Stores settings in ~/.config/tetrs/settings.toml (or platform equivalent)
 
+use crate::custom::CustomMode;
+use crate::daily::DailyRecord;
+use crate::gravity::GravityCurve;
+use crate::mode::GameMode;
+use crate::rotation::RotationSystemKind;
+use crate::rules::{LockReset, Rules};
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -21,6 +28,10 @@ pub struct Settings {
     pub audio: AudioSettings,
     /// High scores
     pub high_scores: HighScores,
+    /// Lifetime Zen stats
+    pub zen_stats: ZenStats,
+    /// Daily challenge attempts by date (YYYY-MM-DD)
+    pub daily: BTreeMap<String, DailyRecord>,
 }
 
 /// Key bindings (stored as strings for easy editing)
@@ -41,11 +52,25 @@ pub struct KeyBindings {
     #[serde(deserialize_with = "deserialize_keys", serialize_with = "serialize_keys")]
     pub rotate_ccw: Vec<String>,
     #[serde(deserialize_with = "deserialize_keys", serialize_with = "serialize_keys")]
+    pub rotate_180: Vec<String>,
+    #[serde(deserialize_with = "deserialize_keys", serialize_with = "serialize_keys")]
     pub hold: Vec<String>,
     #[serde(deserialize_with = "deserialize_keys", serialize_with = "serialize_keys")]
     pub pause: Vec<String>,
     #[serde(deserialize_with = "deserialize_keys", serialize_with = "serialize_keys")]
     pub quit: Vec<String>,
+    /// Practice: take back the last placement
+    #[serde(deserialize_with = "deserialize_keys", serialize_with = "serialize_keys")]
+    pub undo: Vec<String>,
+    /// Practice: place the last undone piece again
+    #[serde(deserialize_with = "deserialize_keys", serialize_with = "serialize_keys")]
+    pub redo: Vec<String>,
+    /// Practice: back to the first piece
+    #[serde(deserialize_with = "deserialize_keys", serialize_with = "serialize_keys")]
+    pub reset: Vec<String>,
+    /// Practice: change the next piece
+    #[serde(deserialize_with = "deserialize_keys", serialize_with = "serialize_keys")]
+    pub next_piece: Vec<String>,
 }
 
 /// Deserialize keys as either a single string or array of strings
@@ -122,6 +147,81 @@ pub struct GameplaySettings {
     pub das_ms: u64,
     /// Auto Repeat Rate in milliseconds
     pub arr_ms: u64,
+    /// Delay before received garbage may enter the board (Versus)
+    pub garbage_delay_ms: u64,
+    /// Rotation system override (None uses the mode's own)
+    pub rotation_system: Option<RotationSystemKind>,
+    /// Lock delay override in milliseconds
+    pub lock_delay_ms: Option<u64>,
+    /// Lock reset behavior override
+    pub lock_reset: Option<LockReset>,
+    /// Lock reset limit override (move reset only)
+    pub lock_reset_limit: Option<u32>,
+    /// Entry delay (ARE) override in milliseconds
+    pub are_ms: Option<u64>,
+    /// Line clear delay override in milliseconds
+    pub line_clear_delay_ms: Option<u64>,
+    /// Use initial rotation (IRS) in modes that allow it
+    pub irs: bool,
+    /// Use initial hold (IHS) in modes that allow it
+    pub ihs: bool,
+    /// Cheese Race: percent chance each garbage row's hole moves (0-100)
+    pub cheese_messiness: u32,
+    /// Zen: fixed gravity at this level's guideline speed (0 for none)
+    pub zen_level: u32,
+    /// Sprint and Ultra: race a ghost of the personal best run
+    pub ghost_race: bool,
+    /// Practice: fixed gravity at this level's guideline speed (0 for none)
+    pub practice_level: u32,
+}
+
+impl GameplaySettings {
+    /// Rules for a game of the given mode, with our overrides applied
+    pub fn rules_for(&self, mode: GameMode) -> Rules {
+        self.with_overrides(mode, mode.rules())
+    }
+
+    /// Rules for a game of a custom mode, with our overrides applied
+    pub fn rules_for_custom(&self, custom: &CustomMode) -> Rules {
+        self.with_overrides(GameMode::Custom, custom.rules())
+    }
+
+    fn with_overrides(&self, mode: GameMode, mut rules: Rules) -> Rules {
+        if let Some(rotation_system) = self.rotation_system {
+            rules.rotation_system = rotation_system;
+        }
+        if let Some(lock_delay_ms) = self.lock_delay_ms {
+            rules.lock_delay_ms = lock_delay_ms;
+        }
+        if let Some(lock_reset) = self.lock_reset {
+            rules.lock_reset = lock_reset;
+        }
+        if let Some(lock_reset_limit) = self.lock_reset_limit {
+            rules.lock_reset_limit = lock_reset_limit;
+        }
+        if let Some(are_ms) = self.are_ms {
+            rules.are_ms = are_ms;
+        }
+        if let Some(line_clear_delay_ms) = self.line_clear_delay_ms {
+            rules.line_clear_delay_ms = line_clear_delay_ms;
+        }
+        rules.irs &= self.irs;
+        rules.ihs &= self.ihs;
+        rules.cheese_messiness = self.cheese_messiness.min(100);
+        let fixed_level = match mode {
+            GameMode::Zen => Some(self.zen_level),
+            GameMode::Practice => Some(self.practice_level),
+            _ => None,
+        };
+        if let Some(level) = fixed_level {
+            let gravity = match level {
+                0 => 0.0,
+                level => GravityCurve::Guideline.at_level(level),
+            };
+            rules.gravity = GravityCurve::Fixed(gravity);
+        }
+        rules
+    }
 }
 
 /// Audio settings
@@ -141,8 +241,28 @@ pub struct AudioSettings {
 #[serde(default)]
 pub struct HighScores {
     pub marathon: Vec<ScoreEntry>,
+    /// Sprint times, `lines` is the distance
     pub sprint: Vec<ScoreEntry>,
     pub ultra: Vec<ScoreEntry>,
+    /// Blitz scores, lock out bonus included
+    pub blitz: Vec<ScoreEntry>,
+    /// Cheese Race times, `lines` is the race length
+    pub cheese: Vec<ScoreEntry>,
+    /// Survival times (longest first)
+    pub survival: Vec<ScoreEntry>,
+    /// Custom mode leaderboards by mode name
+    pub custom: BTreeMap<String, Vec<ScoreEntry>>,
+}
+
+/// Zen totals across every session
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ZenStats {
+    pub sessions: u32,
+    pub time_ms: u64,
+    pub pieces: u64,
+    pub lines: u64,
+    pub score: u64,
 }
 
 /// A single high score entry
@@ -151,10 +271,13 @@ pub struct ScoreEntry {
     pub score: u64,
     pub lines: u32,
     pub level: u32,
-    /// For Sprint mode: time in milliseconds
+    /// For Sprint, Cheese Race and Survival: time in milliseconds
     pub time_ms: Option<u64>,
     /// Date as ISO string
     pub date: String,
+    /// For Sprint: time in milliseconds at every 10 lines
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub splits: Vec<u64>,
 }
 
 impl Default for Settings {
@@ -165,6 +288,8 @@ impl Default for Settings {
             gameplay: GameplaySettings::default(),
             audio: AudioSettings::default(),
             high_scores: HighScores::default(),
+            zen_stats: ZenStats::default(),
+            daily: BTreeMap::new(),
         }
     }
 }
@@ -178,9 +303,14 @@ impl Default for KeyBindings {
             hard_drop: vec!["Space".to_string()],
             rotate_cw: vec!["Up".to_string(), "x".to_string()],
             rotate_ccw: vec!["z".to_string()],
+            rotate_180: vec!["a".to_string()],
             hold: vec!["c".to_string(), "Shift".to_string()],
             pause: vec!["p".to_string(), "Esc".to_string()],
             quit: vec!["q".to_string()],
+            undo: vec!["u".to_string()],
+            redo: vec!["y".to_string()],
+            reset: vec!["r".to_string()],
+            next_piece: vec!["n".to_string()],
         }
     }
 }
@@ -199,6 +329,19 @@ impl Default for GameplaySettings {
         Self {
             das_ms: 170,
             arr_ms: 50,
+            garbage_delay_ms: 500,
+            rotation_system: None,
+            lock_delay_ms: None,
+            lock_reset: None,
+            lock_reset_limit: None,
+            are_ms: None,
+            line_clear_delay_ms: None,
+            irs: true,
+            ihs: true,
+            cheese_messiness: 100,
+            zen_level: 1,
+            ghost_race: false,
+            practice_level: 0,
         }
     }
 }
@@ -215,7 +358,7 @@ impl Default for AudioSettings {
 
 impl Settings {
     /// Get the config directory path
-    fn config_dir() -> Option<PathBuf> {
+    pub fn config_dir() -> Option<PathBuf> {
         ProjectDirs::from("com", "tetrs", "tetrs").map(|dirs| dirs.config_dir().to_path_buf())
     }
 
@@ -231,11 +374,26 @@ impl Settings {
         };
 
         match fs::read_to_string(&path) {
-            Ok(contents) => toml::from_str(&contents).unwrap_or_default(),
+            Ok(contents) => {
+                let mut settings: Self = toml::from_str(&contents).unwrap_or_default();
+                settings.migrate_sprint_scores();
+                settings
+            }
             Err(_) => Self::default(),
         }
     }
 
+    /// Sprint entries saved before there were distances hold the lines
+    /// cleared (40 to 43) rather than the distance. They were all 40 line
+    /// races and, unlike newer entries, have no splits.
+    fn migrate_sprint_scores(&mut self) {
+        let sprint = &mut self.high_scores.sprint;
+        for entry in sprint.iter_mut().filter(|e| e.splits.is_empty()) {
+            entry.lines = 40;
+        }
+        keep_fastest_per_length(sprint);
+    }
+
     /// Save settings to file
     pub fn save(&self) -> Result<(), String> {
         let Some(dir) = Self::config_dir() else {
@@ -266,26 +424,26 @@ impl Settings {
             level,
             time_ms: None,
             date: chrono_lite_now(),
+            splits: Vec::new(),
         };
         self.high_scores.marathon.push(entry);
         self.high_scores.marathon.sort_by(|a, b| b.score.cmp(&a.score));
         self.high_scores.marathon.truncate(10);
     }
 
-    /// Add a high score for Sprint mode (sorted by time, lower is better)
-    pub fn add_sprint_score(&mut self, time_ms: u64, lines: u32, level: u32) {
+    /// Add a Sprint time with its splits (sorted by time, top 10 kept for
+    /// each distance)
+    pub fn add_sprint_score(&mut self, time_ms: u64, distance: u32, level: u32, splits: Vec<u64>) {
         let entry = ScoreEntry {
             score: 0,
-            lines,
+            lines: distance,
             level,
             time_ms: Some(time_ms),
             date: chrono_lite_now(),
+            splits,
         };
         self.high_scores.sprint.push(entry);
-        self.high_scores
-            .sprint
-            .sort_by(|a, b| a.time_ms.cmp(&b.time_ms));
-        self.high_scores.sprint.truncate(10);
+        keep_fastest_per_length(&mut self.high_scores.sprint);
     }
 
     /// Add a high score for Ultra mode
@@ -296,20 +454,132 @@ impl Settings {
             level,
             time_ms: None,
             date: chrono_lite_now(),
+            splits: Vec::new(),
         };
         self.high_scores.ultra.push(entry);
         self.high_scores.ultra.sort_by(|a, b| b.score.cmp(&a.score));
         self.high_scores.ultra.truncate(10);
     }
 
+    /// Add a high score for Blitz mode
+    pub fn add_blitz_score(&mut self, score: u64, lines: u32, level: u32) {
+        let entry = ScoreEntry {
+            score,
+            lines,
+            level,
+            time_ms: None,
+            date: chrono_lite_now(),
+            splits: Vec::new(),
+        };
+        self.high_scores.blitz.push(entry);
+        self.high_scores.blitz.sort_by_key(|e| std::cmp::Reverse(e.score));
+        self.high_scores.blitz.truncate(10);
+    }
+
+    /// Add a Cheese Race time (sorted by time, top 10 kept for each race length)
+    pub fn add_cheese_score(&mut self, time_ms: u64, garbage_lines: u32, level: u32) {
+        let entry = ScoreEntry {
+            score: 0,
+            lines: garbage_lines,
+            level,
+            time_ms: Some(time_ms),
+            date: chrono_lite_now(),
+            splits: Vec::new(),
+        };
+        self.high_scores.cheese.push(entry);
+        keep_fastest_per_length(&mut self.high_scores.cheese);
+    }
+
+    /// Add a Survival time (sorted by time, higher is better)
+    pub fn add_survival_score(&mut self, time_ms: u64, lines: u32, level: u32) {
+        let entry = ScoreEntry {
+            score: 0,
+            lines,
+            level,
+            time_ms: Some(time_ms),
+            date: chrono_lite_now(),
+            splits: Vec::new(),
+        };
+        self.high_scores.survival.push(entry);
+        self.high_scores
+            .survival
+            .sort_by_key(|e| std::cmp::Reverse(e.time_ms));
+        self.high_scores.survival.truncate(10);
+    }
+
+    /// Add a finished Zen session to the lifetime totals
+    pub fn add_zen_session(&mut self, time_ms: u64, pieces: u32, lines: u32, score: u64) {
+        let stats = &mut self.zen_stats;
+        stats.sessions += 1;
+        stats.time_ms += time_ms;
+        stats.pieces += pieces as u64;
+        stats.lines += lines as u64;
+        stats.score += score;
+    }
+
+    /// Count a daily challenge attempt, with its time if it was finished
+    pub fn add_daily_attempt(&mut self, date: &str, finished_ms: Option<u64>) {
+        self.daily.entry(date.to_string()).or_default().add_attempt(finished_ms);
+    }
+
+    /// Add a result for a custom mode, ranked by time for races and by
+    /// score otherwise
+    pub fn add_custom_score(&mut self, custom: &CustomMode, score: u64, time_ms: u64, lines: u32, level: u32) {
+        let entry = ScoreEntry {
+            score,
+            lines,
+            level,
+            time_ms: Some(time_ms),
+            date: chrono_lite_now(),
+            splits: Vec::new(),
+        };
+        let board = self.high_scores.custom.entry(custom.name.clone()).or_default();
+        board.push(entry);
+        if custom.goal.ranked_by_time() {
+            board.sort_by_key(|e| e.time_ms);
+        } else {
+            board.sort_by_key(|e| std::cmp::Reverse(e.score));
+        }
+        board.truncate(10);
+    }
+
     /// Get the best score for Marathon mode
     pub fn best_marathon(&self) -> Option<u64> {
         self.high_scores.marathon.first().map(|e| e.score)
     }
 
-    /// Get the best time for Sprint mode (in ms)
-    pub fn best_sprint(&self) -> Option<u64> {
-        self.high_scores.sprint.first().and_then(|e| e.time_ms)
+    /// Get the best Sprint time for a distance (in ms)
+    pub fn best_sprint(&self, distance: u32) -> Option<u64> {
+        self.sprint_pb(distance).and_then(|e| e.time_ms)
+    }
+
+    /// Get the personal best Sprint run for a distance, splits included
+    pub fn sprint_pb(&self, distance: u32) -> Option<&ScoreEntry> {
+        self.high_scores.sprint.iter().find(|e| e.lines == distance)
+    }
+
+    /// Get the best score for Blitz mode
+    pub fn best_blitz(&self) -> Option<u64> {
+        self.high_scores.blitz.first().map(|e| e.score)
+    }
+
+    /// Get the best Cheese Race time for a race length (in ms)
+    pub fn best_cheese(&self, garbage_lines: u32) -> Option<u64> {
+        self.high_scores
+            .cheese
+            .iter()
+            .find(|e| e.lines == garbage_lines)
+            .and_then(|e| e.time_ms)
+    }
+
+    /// Get the longest Survival time (in ms)
+    pub fn best_survival(&self) -> Option<u64> {
+        self.high_scores.survival.first().and_then(|e| e.time_ms)
+    }
+
+    /// Get the best entry on a custom mode's leaderboard
+    pub fn best_custom(&self, name: &str) -> Option<&ScoreEntry> {
+        self.high_scores.custom.get(name).and_then(|board| board.first())
     }
 
     /// Get the best score for Ultra mode
@@ -318,8 +588,24 @@ impl Settings {
     }
 }
 
+/// Sort races by length then time, keeping the 10 fastest of each length
+fn keep_fastest_per_length(entries: &mut Vec<ScoreEntry>) {
+    entries.sort_by_key(|e| (e.lines, e.time_ms));
+
+    let mut race = None;
+    let mut rank = 0;
+    entries.retain(|e| {
+        if race != Some(e.lines) {
+            race = Some(e.lines);
+            rank = 0;
+        }
+        rank += 1;
+        rank <= 10
+    });
+}
+
 /// Simple date string without external crate
-fn chrono_lite_now() -> String {
+pub(crate) fn chrono_lite_now() -> String {
     // Use system time to create a simple timestamp
     use std::time::{SystemTime, UNIX_EPOCH};
     let duration = SystemTime::now()
@@ -347,3 +633,117 @@ impl VisualSettings {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::custom::Goal;
+
+    #[test]
+    fn test_zen_rules_and_totals() {
+        let mut settings = Settings::default();
+        settings.gameplay.zen_level = 0;
+        assert_eq!(settings.gameplay.rules_for(GameMode::Zen).gravity, GravityCurve::Fixed(0.0));
+        // Other modes keep their own gravity
+        assert_eq!(settings.gameplay.rules_for(GameMode::Marathon).gravity, GravityCurve::Guideline);
+
+        settings.add_zen_session(60_000, 100, 38, 9000);
+        settings.add_zen_session(30_000, 50, 20, 4000);
+        assert_eq!(settings.zen_stats.sessions, 2);
+        assert_eq!(settings.zen_stats.lines, 58);
+        assert_eq!(settings.zen_stats.time_ms, 90_000);
+    }
+
+    #[test]
+    fn test_custom_leaderboards() {
+        let mut settings = Settings::default();
+        let race = CustomMode {
+            name: "Race".to_string(),
+            goal: Goal::Lines(10),
+            ..CustomMode::default()
+        };
+        let attack = CustomMode {
+            name: "Attack".to_string(),
+            goal: Goal::Time(60),
+            ..CustomMode::default()
+        };
+        settings.add_custom_score(&race, 100, 30_000, 10, 2);
+        settings.add_custom_score(&race, 900, 20_000, 10, 2);
+        settings.add_custom_score(&attack, 5000, 60_000, 30, 4);
+        settings.add_custom_score(&attack, 8000, 60_000, 35, 4);
+
+        assert_eq!(settings.best_custom("Race").and_then(|e| e.time_ms), Some(20_000));
+        assert_eq!(settings.best_custom("Attack").map(|e| e.score), Some(8000));
+        assert!(settings.best_custom("Missing").is_none());
+    }
+
+    #[test]
+    fn test_cheese_scores_per_race_length() {
+        let mut settings = Settings::default();
+        for time_ms in (1..=12).rev() {
+            settings.add_cheese_score(time_ms * 1000, 10, 1);
+        }
+        settings.add_cheese_score(90_000, 100, 3);
+        settings.add_cheese_score(500, 18, 1);
+
+        assert_eq!(settings.best_cheese(10), Some(1000));
+        assert_eq!(settings.best_cheese(18), Some(500));
+        assert_eq!(settings.best_cheese(100), Some(90_000));
+        // Only the 10 best of the 10-line race are kept
+        assert_eq!(settings.high_scores.cheese.iter().filter(|e| e.lines == 10).count(), 10);
+    }
+
+    #[test]
+    fn test_daily_history() {
+        let mut settings = Settings::default();
+        settings.add_daily_attempt("2026-10-16", Some(80_000));
+        settings.add_daily_attempt("2026-10-17", None);
+        settings.add_daily_attempt("2026-10-17", Some(75_000));
+
+        let loaded: Settings = toml::from_str(&toml::to_string_pretty(&settings).unwrap()).unwrap();
+        assert_eq!(loaded.daily.len(), 2);
+        assert_eq!(loaded.daily["2026-10-17"].attempts, 2);
+        assert_eq!(loaded.daily["2026-10-17"].best_ms, Some(75_000));
+    }
+
+    #[test]
+    fn test_sprint_scores_per_distance() {
+        let mut settings = Settings::default();
+        settings.add_sprint_score(40_000, 40, 5, vec![9_000, 19_000, 30_000, 40_000]);
+        settings.add_sprint_score(35_000, 40, 5, vec![8_000, 17_000, 26_000, 35_000]);
+        settings.add_sprint_score(15_000, 20, 5, vec![7_000, 15_000]);
+
+        assert_eq!(settings.best_sprint(40), Some(35_000));
+        assert_eq!(settings.best_sprint(20), Some(15_000));
+        assert_eq!(settings.best_sprint(100), None);
+        assert_eq!(settings.sprint_pb(40).map(|e| e.splits.len()), Some(4));
+
+        // Splits survive a save and load
+        let loaded: Settings = toml::from_str(&toml::to_string_pretty(&settings).unwrap()).unwrap();
+        assert_eq!(loaded.high_scores.sprint[0].splits, [7_000, 15_000]);
+    }
+
+    #[test]
+    fn test_old_sprint_scores_migrated() {
+        let mut settings = Settings::default();
+        settings.add_sprint_score(38_000, 40, 5, vec![9_000, 19_000, 29_000, 38_000]);
+        settings.add_sprint_score(15_000, 20, 5, vec![7_000, 15_000]);
+        // Saved before distances: lines cleared, overshooting 40, no splits
+        for (time_ms, lines) in [(36_000, 42), (41_000, 41)] {
+            settings.high_scores.sprint.push(ScoreEntry {
+                score: 0,
+                lines,
+                level: 5,
+                time_ms: Some(time_ms),
+                date: String::new(),
+                splits: Vec::new(),
+            });
+        }
+
+        settings.migrate_sprint_scores();
+        assert_eq!(settings.best_sprint(40), Some(36_000));
+        assert_eq!(settings.best_sprint(20), Some(15_000));
+        assert!(settings.high_scores.sprint.iter().all(|e| e.lines == 20 || e.lines == 40));
+        assert_eq!(settings.high_scores.sprint.len(), 4);
+    }
+}
@@ -2,56 +2,59 @@
 //!
 //! SRS defines the wall kicks attempted when rotating a piece.
 //! If a rotation would cause collision, these offsets are tried in order.
+//! SRS itself has no 180 rotation, so 180 kicks use the SRS+ table.
 
 use crate::tetromino::{Rotation, RotationDirection, TetrominoType};
 
 /// Get wall kick offsets for a rotation attempt
-/// Returns up to 5 (row_offset, col_offset) pairs to try
+/// Returns the (row_offset, col_offset) pairs to try, in order
 pub fn get_wall_kicks(
     piece_type: TetrominoType,
     from: Rotation,
     direction: RotationDirection,
-) -> [(i32, i32); 5] {
-    match piece_type {
-        TetrominoType::O => {
+) -> &'static [(i32, i32)] {
+    match (piece_type, direction) {
+        (TetrominoType::O, _) => {
             // O piece doesn't rotate, but we return identity kicks
-            [(0, 0); 5]
+            &[(0, 0); 5]
         }
-        TetrominoType::I => i_piece_kicks(from, direction),
+        (_, RotationDirection::Half) => half_kicks(from),
+        (TetrominoType::I, _) => i_piece_kicks(from, direction),
         _ => jlstz_kicks(from, direction),
     }
 }
 
 /// Wall kicks for J, L, S, T, Z pieces
 /// Standard SRS with row-up coordinate system (row offsets negated from spec)
-fn jlstz_kicks(from: Rotation, direction: RotationDirection) -> [(i32, i32); 5] {
+fn jlstz_kicks(from: Rotation, direction: RotationDirection) -> &'static [(i32, i32)] {
     use Rotation::*;
     use RotationDirection::*;
 
     // Kicks are (row_offset, col_offset) where row+ is up, col+ is right
     match (from, direction) {
         // 0→R (North → East CW)
-        (North, Clockwise) => [(0, 0), (0, -1), (1, -1), (-2, 0), (-2, -1)],
+        (North, Clockwise) => &[(0, 0), (0, -1), (1, -1), (-2, 0), (-2, -1)],
         // R→0 (East → North CCW)
-        (East, CounterClockwise) => [(0, 0), (0, 1), (-1, 1), (2, 0), (2, 1)],
+        (East, CounterClockwise) => &[(0, 0), (0, 1), (-1, 1), (2, 0), (2, 1)],
         // R→2 (East → South CW)
-        (East, Clockwise) => [(0, 0), (0, 1), (-1, 1), (2, 0), (2, 1)],
+        (East, Clockwise) => &[(0, 0), (0, 1), (-1, 1), (2, 0), (2, 1)],
         // 2→R (South → East CCW)
-        (South, CounterClockwise) => [(0, 0), (0, -1), (1, -1), (-2, 0), (-2, -1)],
+        (South, CounterClockwise) => &[(0, 0), (0, -1), (1, -1), (-2, 0), (-2, -1)],
         // 2→L (South → West CW)
-        (South, Clockwise) => [(0, 0), (0, 1), (1, 1), (-2, 0), (-2, 1)],
+        (South, Clockwise) => &[(0, 0), (0, 1), (1, 1), (-2, 0), (-2, 1)],
         // L→2 (West → South CCW)
-        (West, CounterClockwise) => [(0, 0), (0, -1), (-1, -1), (2, 0), (2, -1)],
+        (West, CounterClockwise) => &[(0, 0), (0, -1), (-1, -1), (2, 0), (2, -1)],
         // L→0 (West → North CW)
-        (West, Clockwise) => [(0, 0), (0, -1), (-1, -1), (2, 0), (2, -1)],
+        (West, Clockwise) => &[(0, 0), (0, -1), (-1, -1), (2, 0), (2, -1)],
         // 0→L (North → West CCW)
-        (North, CounterClockwise) => [(0, 0), (0, 1), (1, 1), (-2, 0), (-2, 1)],
+        (North, CounterClockwise) => &[(0, 0), (0, 1), (1, 1), (-2, 0), (-2, 1)],
+        (_, Half) => half_kicks(from),
     }
 }
 
 /// Wall kicks for I piece (different from other pieces)
 /// Standard SRS: (row_offset, col_offset) where row+ is up, col+ is right
-fn i_piece_kicks(from: Rotation, direction: RotationDirection) -> [(i32, i32); 5] {
+fn i_piece_kicks(from: Rotation, direction: RotationDirection) -> &'static [(i32, i32)] {
     use Rotation::*;
     use RotationDirection::*;
 
@@ -59,21 +62,39 @@ fn i_piece_kicks(from: Rotation, direction: RotationDirection) -> [(i32, i32); 5
     // SRS wiki format (x, y) maps to (y, x) = (row, col)
     match (from, direction) {
         // 0→R (North → East CW): (0,0), (-2,0), (+1,0), (-2,-1), (+1,+2)
-        (North, Clockwise) => [(0, 0), (0, -2), (0, 1), (-1, -2), (2, 1)],
+        (North, Clockwise) => &[(0, 0), (0, -2), (0, 1), (-1, -2), (2, 1)],
         // R→0 (East → North CCW): (0,0), (+2,0), (-1,0), (+2,+1), (-1,-2)
-        (East, CounterClockwise) => [(0, 0), (0, 2), (0, -1), (1, 2), (-2, -1)],
+        (East, CounterClockwise) => &[(0, 0), (0, 2), (0, -1), (1, 2), (-2, -1)],
         // R→2 (East → South CW): (0,0), (-1,0), (+2,0), (-1,+2), (+2,-1)
-        (East, Clockwise) => [(0, 0), (0, -1), (0, 2), (2, -1), (-1, 2)],
+        (East, Clockwise) => &[(0, 0), (0, -1), (0, 2), (2, -1), (-1, 2)],
         // 2→R (South → East CCW): (0,0), (+1,0), (-2,0), (+1,-2), (-2,+1)
-        (South, CounterClockwise) => [(0, 0), (0, 1), (0, -2), (-2, 1), (1, -2)],
+        (South, CounterClockwise) => &[(0, 0), (0, 1), (0, -2), (-2, 1), (1, -2)],
         // 2→L (South → West CW): (0,0), (+2,0), (-1,0), (+2,+1), (-1,-2)
-        (South, Clockwise) => [(0, 0), (0, 2), (0, -1), (1, 2), (-2, -1)],
+        (South, Clockwise) => &[(0, 0), (0, 2), (0, -1), (1, 2), (-2, -1)],
         // L→2 (West → South CCW): (0,0), (-2,0), (+1,0), (-2,-1), (+1,+2)
-        (West, CounterClockwise) => [(0, 0), (0, -2), (0, 1), (-1, -2), (2, 1)],
+        (West, CounterClockwise) => &[(0, 0), (0, -2), (0, 1), (-1, -2), (2, 1)],
         // L→0 (West → North CW): (0,0), (+1,0), (-2,0), (+1,-2), (-2,+1)
-        (West, Clockwise) => [(0, 0), (0, 1), (0, -2), (-2, 1), (1, -2)],
+        (West, Clockwise) => &[(0, 0), (0, 1), (0, -2), (-2, 1), (1, -2)],
         // 0→L (North → West CCW): (0,0), (-1,0), (+2,0), (-1,+2), (+2,-1)
-        (North, CounterClockwise) => [(0, 0), (0, -1), (0, 2), (2, -1), (-1, 2)],
+        (North, CounterClockwise) => &[(0, 0), (0, -1), (0, 2), (2, -1), (-1, 2)],
+        (_, Half) => half_kicks(from),
+    }
+}
+
+/// 180 wall kicks (SRS+), shared by every piece except O
+/// SRS+ table format (x, y) maps to (y, x) = (row, col)
+fn half_kicks(from: Rotation) -> &'static [(i32, i32)] {
+    use Rotation::*;
+
+    match from {
+        // 0→2: (0,0), (0,+1), (+1,+1), (-1,+1), (+1,0), (-1,0)
+        North => &[(0, 0), (1, 0), (1, 1), (1, -1), (0, 1), (0, -1)],
+        // R→L: (0,0), (+1,0), (+1,+2), (+1,+1), (0,+2), (0,+1)
+        East => &[(0, 0), (0, 1), (2, 1), (1, 1), (2, 0), (1, 0)],
+        // 2→0: (0,0), (0,-1), (-1,-1), (+1,-1), (-1,0), (+1,0)
+        South => &[(0, 0), (-1, 0), (-1, -1), (-1, 1), (0, -1), (0, 1)],
+        // L→R: (0,0), (-1,0), (-1,+2), (-1,+1), (0,+2), (0,+1)
+        West => &[(0, 0), (0, -1), (2, -1), (1, -1), (2, 0), (1, 0)],
     }
 }
 
@@ -83,7 +104,7 @@ mod tests {
 
     #[test]
     fn test_kick_count() {
-        // All rotations should return exactly 5 kicks
+        // All quarter rotations should return exactly 5 kicks
         for piece in TetrominoType::all() {
             for from in [
                 Rotation::North,
@@ -109,7 +130,11 @@ mod tests {
                 Rotation::South,
                 Rotation::West,
             ] {
-                for dir in [RotationDirection::Clockwise, RotationDirection::CounterClockwise] {
+                for dir in [
+                    RotationDirection::Clockwise,
+                    RotationDirection::CounterClockwise,
+                    RotationDirection::Half,
+                ] {
                     let kicks = get_wall_kicks(piece, from, dir);
                     assert_eq!(kicks[0], (0, 0));
                 }
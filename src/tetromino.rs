@@ -2,7 +2,7 @@
 //!
 //! All 7 standard tetrominoes with their rotations using SRS (Super Rotation System)
 
-use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
 
 /// The 7 tetromino types
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -17,19 +17,6 @@ pub enum TetrominoType {
 }
 
 impl TetrominoType {
-    /// Get the color for this tetromino
-    pub fn color(&self) -> Color {
-        match self {
-            TetrominoType::I => Color::Cyan,
-            TetrominoType::O => Color::Yellow,
-            TetrominoType::T => Color::Magenta,
-            TetrominoType::S => Color::Green,
-            TetrominoType::Z => Color::Red,
-            TetrominoType::J => Color::Blue,
-            TetrominoType::L => Color::Rgb(255, 165, 0), // Orange
-        }
-    }
-
     /// Get all tetromino types for bag randomization
     pub fn all() -> [TetrominoType; 7] {
         [
@@ -139,11 +126,27 @@ impl Rotation {
             Rotation::East => Rotation::North,
         }
     }
+
+    /// Rotate 180 degrees: North ↔ South, East ↔ West
+    pub fn flip(&self) -> Rotation {
+        self.cw().cw()
+    }
+
+    /// Rotation state after turning in the given direction
+    pub fn rotated(&self, direction: RotationDirection) -> Rotation {
+        match direction {
+            RotationDirection::Clockwise => self.cw(),
+            RotationDirection::CounterClockwise => self.ccw(),
+            RotationDirection::Half => self.flip(),
+        }
+    }
 }
 
 /// Direction for rotation
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum RotationDirection {
     Clockwise,
     CounterClockwise,
+    /// 180 degrees
+    Half,
 }
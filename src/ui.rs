@@ -1,11 +1,15 @@
 //! Terminal UI rendering with ratatui
 
-use crate::board::{Cell, BOARD_HEIGHT, BOARD_WIDTH};
-use crate::game::{Game, GameState};
+use tetrs::board::{Board, Cell};
+use tetrs::game::{Game, GameState};
 use crate::menu::{Menu, MenuItemType, MenuScreen};
-use crate::mode::GameMode;
-use crate::settings::Settings;
-use crate::tetromino::TetrominoType;
+use tetrs::custom::Goal;
+use tetrs::mode::{GameMode, ModeState};
+use tetrs::replay::ReplayPlayer;
+use tetrs::rotation::RotationSystemKind;
+use tetrs::rules::Rules;
+use tetrs::settings::{ScoreEntry, Settings};
+use tetrs::tetromino::TetrominoType;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Style, Stylize},
@@ -16,33 +20,81 @@ use ratatui::{
 
 const EMPTY: &str = "  ";
 
-/// Total width needed: hold(12) + board(22) + next/stats(16) = 50
-const GAME_WIDTH: u16 = 50;
-/// Total height needed: board(20) + 2 buffer rows + 2 for borders = 24
+/// Width of the hold box left of the board
+const HOLD_WIDTH: u16 = 12;
+/// Width of the next queue and stats right of the board
+const PANEL_WIDTH: u16 = 16;
+/// Minimum height: next queue(14) + stats, same as a standard board(24)
 const GAME_HEIGHT: u16 = 24;
 /// Number of rows to show above the visible board (spawn area)
 const VISIBLE_BUFFER: usize = 2;
 
-/// Render the main menu
-pub fn render_menu(frame: &mut Frame, menu: &Menu) {
-    let area = frame.area();
+/// Board box size (width, height): 2 chars per column, buffer rows, borders
+fn board_box_size(board: &Board) -> (u16, u16) {
+    let width = board.width() * 2 + 2;
+    let height = board.height() + VISIBLE_BUFFER + 2;
+    (width as u16, height as u16)
+}
 
-    // Determine menu size based on screen type
-    let (menu_width, menu_height) = match menu.screen {
-        MenuScreen::Main | MenuScreen::ModeSelect => (44u16, 18u16),
+/// Single-player game size (width, height): hold + board + next/stats
+fn game_size(board: &Board) -> (u16, u16) {
+    let (board_width, board_height) = board_box_size(board);
+    (HOLD_WIDTH + board_width + PANEL_WIDTH, board_height.max(GAME_HEIGHT))
+}
+
+/// Menu size (width, height) for each screen type
+pub fn menu_size(screen: &MenuScreen) -> (u16, u16) {
+    match screen {
+        MenuScreen::Main => (44u16, 18u16),
+        MenuScreen::ModeSelect => (44u16, 37u16),
+        MenuScreen::Sprint => (44u16, 18u16),
+        MenuScreen::CheeseRace => (44u16, 16u16),
+        MenuScreen::Daily => (60u16, 28u16),
+        MenuScreen::CustomModes => (56u16, 26u16),
+        MenuScreen::CustomBuilder => (50u16, 32u16),
         MenuScreen::Settings => (44u16, 16u16),
-        MenuScreen::SettingsKeys => (50u16, 24u16),
-        MenuScreen::SettingsVisual | MenuScreen::SettingsGameplay | MenuScreen::SettingsAudio => (50u16, 14u16),
+        MenuScreen::SettingsKeys => (50u16, 34u16),
+        MenuScreen::SettingsVisual | MenuScreen::SettingsAudio => (50u16, 14u16),
+        MenuScreen::SettingsGameplay => (50u16, 40u16),
         MenuScreen::Multiplayer => (44u16, 14u16),
         MenuScreen::HostGame | MenuScreen::JoinGame => (60u16, 14u16),
+        MenuScreen::Replays => (56u16, 26u16),
         _ => (44u16, 16u16),
-    };
+    }
+}
+
+/// Title height above the menu box for each screen type
+pub fn menu_title_height(screen: &MenuScreen) -> u16 {
+    if matches!(screen, MenuScreen::Main | MenuScreen::ModeSelect) {
+        6
+    } else {
+        3
+    }
+}
+
+/// First visible item and how many items fit when the menu is drawn in `area`,
+/// scrolled so the selected item stays on screen
+pub fn menu_scroll(menu: &Menu, area: Rect) -> (usize, usize) {
+    let (_, menu_height) = menu_size(&menu.screen);
+    let inner_height = menu_height.min(area.height).saturating_sub(menu_title_height(&menu.screen) + 2);
+    // One spacer row above the items and two rows for the hint below, two rows per item
+    let fits = (inner_height.saturating_sub(3) / 2).max(1) as usize;
+    let first = (menu.selected + 1).saturating_sub(fits);
+    (first, fits.min(menu.items.len().saturating_sub(first)))
+}
+
+/// Render the main menu
+pub fn render_menu(frame: &mut Frame, menu: &Menu) {
+    let area = frame.area();
+
+    // Determine menu size based on screen type
+    let (menu_width, menu_height) = menu_size(&menu.screen);
 
     let menu_area = center_rect(area, menu_width, menu_height);
 
     // Title area height depends on screen
     let show_big_title = matches!(menu.screen, MenuScreen::Main | MenuScreen::ModeSelect);
-    let title_height = if show_big_title { 6u16 } else { 3u16 };
+    let title_height = menu_title_height(&menu.screen);
 
     let layout = Layout::default()
         .direction(Direction::Vertical)
@@ -67,6 +119,11 @@ pub fn render_menu(frame: &mut Frame, menu: &Menu) {
     } else {
         // Smaller title for settings screens
         let screen_title = match menu.screen {
+            MenuScreen::Sprint => "SPRINT",
+            MenuScreen::CheeseRace => "CHEESE RACE",
+            MenuScreen::Daily => "DAILY CHALLENGE",
+            MenuScreen::CustomModes => "CUSTOM MODES",
+            MenuScreen::CustomBuilder => "NEW CUSTOM MODE",
             MenuScreen::Settings => "SETTINGS",
             MenuScreen::SettingsKeys => "KEY BINDINGS",
             MenuScreen::SettingsVisual => "VISUAL SETTINGS",
@@ -75,6 +132,7 @@ pub fn render_menu(frame: &mut Frame, menu: &Menu) {
             MenuScreen::Multiplayer => "MULTIPLAYER",
             MenuScreen::HostGame => "HOST GAME",
             MenuScreen::JoinGame => "JOIN GAME",
+            MenuScreen::Replays => "REPLAYS",
             _ => "TETRS",
         };
         let title_lines = vec![
@@ -92,10 +150,14 @@ pub fn render_menu(frame: &mut Frame, menu: &Menu) {
     let inner = block.inner(layout[1]);
     frame.render_widget(block, layout[1]);
 
+    let (first, visible) = menu_scroll(menu, area);
+    let more_style = Style::default().fg(Color::DarkGray);
+
     let mut lines = Vec::new();
-    lines.push(Line::raw("")); // Spacing
+    // Spacing, or a marker when items are scrolled off the top
+    lines.push(if first > 0 { Line::styled("▲", more_style) } else { Line::raw("") });
 
-    for (i, item) in menu.items.iter().enumerate() {
+    for (i, item) in menu.items.iter().enumerate().skip(first).take(visible) {
         let is_selected = i == menu.selected;
         let is_rebinding = menu.rebinding == Some(i);
 
@@ -105,6 +167,10 @@ pub fn render_menu(frame: &mut Frame, menu: &Menu) {
     }
 
     // Controls hint based on screen and current item type
+    if first + visible < menu.items.len() {
+        lines.pop();
+        lines.push(Line::styled("▼", more_style));
+    }
     lines.push(Line::raw(""));
     let hint = get_controls_hint(menu);
     lines.push(Line::styled(hint, Style::default().fg(Color::DarkGray)));
@@ -221,26 +287,41 @@ fn get_controls_hint(menu: &Menu) -> String {
     }
 }
 
-/// Render the entire game UI
-pub fn render_game(frame: &mut Frame, game: &Game, settings: &Settings) {
+/// Render the entire game UI, with the PB ghost on the right if racing one
+pub fn render_game(frame: &mut Frame, game: &Game, ghost: Option<&Game>, settings: &Settings) {
     let area = frame.area();
     let (block_char, _) = settings.visual.block_chars();
 
     // Center the game area
-    let game_area = center_rect(area, GAME_WIDTH, GAME_HEIGHT);
+    let (width, height) = game_size(&game.board);
+    let ghost_width = ghost.map_or(0, |ghost| ghost.board.width() as u16 + 2);
+    let game_area = center_rect(area, width + ghost_width, height);
+    let (board_width, _) = board_box_size(&game.board);
 
-    // Create main layout: hold | board | next + stats
+    // Create main layout: hold | board | next + stats | ghost
     let main_layout = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
-            Constraint::Length(12), // Hold box
-            Constraint::Length(22), // Board (10*2 + 2 for borders)
-            Constraint::Length(16), // Next queue + stats
+            Constraint::Length(HOLD_WIDTH),  // Hold box
+            Constraint::Length(board_width), // Board (columns*2 + 2 for borders)
+            Constraint::Length(PANEL_WIDTH), // Next queue + stats
+            Constraint::Length(ghost_width), // PB ghost
         ])
         .split(game_area);
 
-    // Render hold piece
-    render_hold(frame, main_layout[0], game.hold_piece, block_char);
+    // Left side: hold, and splits against the PB in Sprint
+    let left_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(match game.mode() {
+            GameMode::Sprint(_) => [Constraint::Length(6), Constraint::Min(0)],
+            _ => [Constraint::Min(0), Constraint::Length(0)],
+        })
+        .split(main_layout[0]);
+
+    render_hold(frame, left_layout[0], game.hold_piece, game.rules().rotation_system, block_char);
+    if let GameMode::Sprint(distance) = game.mode() {
+        render_splits(frame, left_layout[1], game, settings.sprint_pb(distance));
+    }
 
     // Render main board
     render_board(frame, main_layout[1], game, settings);
@@ -254,28 +335,89 @@ pub fn render_game(frame: &mut Frame, game: &Game, settings: &Settings) {
         ])
         .split(main_layout[2]);
 
-    render_next_queue(frame, right_layout[0], game.preview(), block_char);
+    render_next_queue(frame, right_layout[0], game.preview(), game.rules().rotation_system, block_char);
     render_stats(frame, right_layout[1], game);
 
+    if let Some(ghost) = ghost {
+        render_ghost(frame, main_layout[3], ghost, block_char);
+    }
+
     // Overlays
     match game.state {
         GameState::Countdown(n) => render_countdown(frame, area, n),
         GameState::Paused => render_overlay(frame, area, "PAUSED", "Press P to resume"),
         GameState::GameOver => {
-            let subtitle = match game.mode() {
-                GameMode::Ultra => "Time's up!",
-                _ => "Press any key",
+            let subtitle = match (game.mode(), &game.mode_state.master) {
+                (_, Some(master)) => format!("Grade: {}", master.grade()),
+                (GameMode::Ultra, _) => "Time's up!".to_string(),
+                (GameMode::Custom, _) if matches!(game.mode_state.custom_goal(), Some(Goal::Time(_))) => {
+                    "Time's up!".to_string()
+                }
+                (GameMode::Blitz, _) => {
+                    format!("Lock out bonus: +{}", game.mode_state.lockout_bonus.unwrap_or(0))
+                }
+                (GameMode::Survival, _) => format!("Survived: {}", game.mode_state.format_time()),
+                (GameMode::Zen, _) => format!("{} lines this session", game.score.lines),
+                _ => "Press any key".to_string(),
             };
-            render_overlay(frame, area, "GAME OVER", subtitle);
+            render_results(frame, area, "GAME OVER", &subtitle, game.rules());
         }
         GameState::Victory => {
-            let time = game.mode_state.format_time();
-            render_overlay(frame, area, "COMPLETE!", &format!("Time: {}", time));
+            let subtitle = match &game.mode_state.master {
+                Some(master) => format!("Grade: {}", master.grade()),
+                None => format!("Time: {}", game.mode_state.format_time()),
+            };
+            render_results(frame, area, "COMPLETE!", &subtitle, game.rules());
         }
         GameState::Playing => {}
     }
 }
 
+/// Render replay playback: the recorded game plus a status bar
+pub fn render_replay(frame: &mut Frame, player: &ReplayPlayer, settings: &Settings) {
+    render_game(frame, &player.game, None, settings);
+
+    let area = frame.area();
+    let (width, height) = game_size(&player.game.board);
+    let game_area = center_rect(area, width, height);
+    let status_y = (game_area.y + game_area.height).min(area.height.saturating_sub(2));
+    let status_area = Rect::new(area.x, status_y, area.width, 2.min(area.height));
+
+    let state = if player.is_finished() {
+        "■ END"
+    } else if player.paused {
+        "❚❚ PAUSED"
+    } else {
+        "▶ PLAYING"
+    };
+
+    let mut status = vec![
+        Span::styled(" REPLAY ", Style::default().fg(Color::Black).bg(Color::Cyan).bold()),
+        Span::styled(format!("  {}  ", state), Style::default().fg(Color::Yellow).bold()),
+        Span::styled(format!("{}x  ", player.speed()), Style::default().fg(Color::Cyan)),
+        Span::styled(
+            format!("Piece {}/{}", player.current_piece(), player.total_pieces),
+            Style::default().fg(Color::White),
+        ),
+    ];
+    if !player.seek_input.is_empty() {
+        status.push(Span::styled(
+            format!("  Go to piece: {}_", player.seek_input),
+            Style::default().fg(Color::Green),
+        ));
+    }
+
+    let lines = vec![
+        Line::from(status),
+        Line::styled(
+            "Space Pause  ←→ Step piece  ↑↓ Speed  0-9 Enter Go to piece  Esc Exit",
+            Style::default().fg(Color::DarkGray),
+        ),
+    ];
+    let paragraph = Paragraph::new(lines).alignment(Alignment::Center);
+    frame.render_widget(paragraph, status_area);
+}
+
 /// Center a rect within another rect
 fn center_rect(area: Rect, width: u16, height: u16) -> Rect {
     let x = area.x + area.width.saturating_sub(width) / 2;
@@ -289,7 +431,13 @@ fn center_rect(area: Rect, width: u16, height: u16) -> Rect {
 }
 
 /// Render the hold piece box
-fn render_hold(frame: &mut Frame, area: Rect, hold: Option<TetrominoType>, block_char: &str) {
+fn render_hold(
+    frame: &mut Frame,
+    area: Rect,
+    hold: Option<TetrominoType>,
+    system: RotationSystemKind,
+    block_char: &str,
+) {
     let block = Block::default()
         .title(" HOLD ")
         .title_alignment(Alignment::Center)
@@ -300,12 +448,60 @@ fn render_hold(frame: &mut Frame, area: Rect, hold: Option<TetrominoType>, block
     frame.render_widget(block, area);
 
     if let Some(piece_type) = hold {
-        render_mini_piece(frame, inner, piece_type, block_char);
+        render_mini_piece(frame, inner, piece_type, system, block_char);
     }
 }
 
+/// Render Sprint splits, each as its delta to the PB's split (green when
+/// ahead, red when behind), or the split time if the PB has none
+fn render_splits(frame: &mut Frame, area: Rect, game: &Game, pb: Option<&ScoreEntry>) {
+    let block = Block::default()
+        .title(" SPLITS ")
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Gray));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let pb_splits = pb.map_or(&[][..], |pb| pb.splits.as_slice());
+    let splits = &game.mode_state.splits;
+    // Newest splits at the bottom, dropping the oldest when out of room
+    let first = splits.len().saturating_sub(inner.height as usize);
+
+    let lines: Vec<Line> = splits
+        .iter()
+        .enumerate()
+        .skip(first)
+        .map(|(i, split)| {
+            let split_lines = (i as u32 + 1) * ModeState::SPLIT_LINES;
+            let split_ms = split.as_millis() as i64;
+            let (text, color) = match pb_splits.get(i) {
+                Some(&pb_ms) => {
+                    let delta = split_ms - pb_ms as i64;
+                    let color = if delta <= 0 { Color::Green } else { Color::Red };
+                    (format!("{:+.1}", delta as f64 / 1000.0), color)
+                }
+                None => (format!("{:.1}", split_ms as f64 / 1000.0), Color::White),
+            };
+            Line::from(vec![
+                Span::styled(format!("{:>4} ", split_lines), Style::default().fg(Color::Gray)),
+                Span::styled(text, Style::default().fg(color)),
+            ])
+        })
+        .collect();
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
 /// Render the next piece queue
-fn render_next_queue(frame: &mut Frame, area: Rect, queue: &[TetrominoType], block_char: &str) {
+fn render_next_queue(
+    frame: &mut Frame,
+    area: Rect,
+    queue: &[TetrominoType],
+    system: RotationSystemKind,
+    block_char: &str,
+) {
     let block = Block::default()
         .title(" NEXT ")
         .title_alignment(Alignment::Center)
@@ -327,18 +523,47 @@ fn render_next_queue(frame: &mut Frame, area: Rect, queue: &[TetrominoType], blo
         .split(inner);
 
     for (i, &piece_type) in queue.iter().take(num_pieces).enumerate() {
-        render_mini_piece(frame, piece_areas[i], piece_type, block_char);
+        render_mini_piece(frame, piece_areas[i], piece_type, system, block_char);
+    }
+}
+
+/// Get the display color for a tetromino
+fn piece_color(piece_type: TetrominoType) -> Color {
+    match piece_type {
+        TetrominoType::I => Color::Cyan,
+        TetrominoType::O => Color::Yellow,
+        TetrominoType::T => Color::Magenta,
+        TetrominoType::S => Color::Green,
+        TetrominoType::Z => Color::Red,
+        TetrominoType::J => Color::Blue,
+        TetrominoType::L => Color::Rgb(255, 165, 0), // Orange
     }
 }
 
-/// Render a small piece preview (for hold and next queue)
-fn render_mini_piece(frame: &mut Frame, area: Rect, piece_type: TetrominoType, block_char: &str) {
+/// Get the display color for a board cell (None when empty)
+fn cell_color(cell: Cell) -> Option<Color> {
+    match cell {
+        Cell::Empty => None,
+        Cell::Filled(piece_type) => Some(piece_color(piece_type)),
+        Cell::Garbage => Some(Color::Gray),
+        Cell::Bone => Some(Color::White),
+    }
+}
+
+/// Render a small piece preview in spawn orientation (for hold and next queue)
+fn render_mini_piece(
+    frame: &mut Frame,
+    area: Rect,
+    piece_type: TetrominoType,
+    system: RotationSystemKind,
+    block_char: &str,
+) {
     if area.height < 1 || area.width < 4 {
         return;
     }
 
-    let color = piece_type.color();
-    let shape = piece_type.shape(crate::tetromino::Rotation::North);
+    let color = piece_color(piece_type);
+    let shape = system.system().shape(piece_type, tetrs::tetromino::Rotation::North);
 
     // Find bounding box to normalize coordinates
     let max_row = shape.iter().map(|(r, _)| *r).max().unwrap_or(0);
@@ -369,9 +594,9 @@ fn render_mini_piece(frame: &mut Frame, area: Rect, piece_type: TetrominoType, b
 /// Render the game board
 fn render_board(frame: &mut Frame, area: Rect, game: &Game, settings: &Settings) {
     let (block_char, ghost_char) = settings.visual.block_chars();
-    let show_ghost = settings.visual.show_ghost;
+    let show_ghost = settings.visual.show_ghost && game.rules().ghost;
 
-    let title = format!(" {} ", game.mode().name());
+    let title = format!(" {} ", game.mode_name());
     let block = Block::default()
         .title(title)
         .title_alignment(Alignment::Center)
@@ -385,21 +610,25 @@ fn render_board(frame: &mut Frame, area: Rect, game: &Game, settings: &Settings)
     let mut lines: Vec<Line> = Vec::new();
 
     // Total visible rows: main board + buffer zone above
-    let total_visible_rows = BOARD_HEIGHT + VISIBLE_BUFFER;
+    let board_height = game.board.height();
+    let total_visible_rows = board_height + VISIBLE_BUFFER;
+    // Boards taller than the screen lose their top rows, never the stack
+    let hidden_rows = total_visible_rows.saturating_sub(inner.height as usize);
+    let stack_hidden = game.stack_hidden();
 
     // Render from top to bottom (buffer rows first, then main board)
-    for row in (0..total_visible_rows).rev() {
+    for row in (0..total_visible_rows).rev().skip(hidden_rows) {
         let mut spans = Vec::new();
-        let is_buffer_row = row >= BOARD_HEIGHT;
+        let is_buffer_row = row >= board_height;
 
-        for col in 0..BOARD_WIDTH {
+        for col in 0..game.board.width() {
             // Check for current piece (visible in both main board and buffer)
             let current_block = game.current_piece.as_ref().and_then(|piece| {
                 if piece
                     .block_positions()
                     .contains(&(row as i32, col as i32))
                 {
-                    Some((piece.piece_type.color(), false))
+                    Some((piece_color(piece.piece_type), false))
                 } else {
                     None
                 }
@@ -409,10 +638,10 @@ fn render_board(frame: &mut Frame, area: Rect, game: &Game, settings: &Settings)
             let ghost_block = if show_ghost && !is_buffer_row {
                 game.current_piece.as_ref().and_then(|piece| {
                     let ghost_row = piece.ghost_row(&game.board);
-                    let offsets = piece.piece_type.shape(piece.rotation);
+                    let offsets = piece.shape();
                     for (dr, dc) in offsets {
                         if ghost_row + dr == row as i32 && piece.col + dc == col as i32 {
-                            return Some((piece.piece_type.color(), true));
+                            return Some((piece_color(piece.piece_type), true));
                         }
                     }
                     None
@@ -426,13 +655,13 @@ fn render_board(frame: &mut Frame, area: Rect, game: &Game, settings: &Settings)
                 (block_char, Style::default().fg(color))
             } else if let Some((color, _)) = ghost_block {
                 (ghost_char, Style::default().fg(color).dim())
-            } else if is_buffer_row {
+            } else if is_buffer_row || stack_hidden {
                 // Buffer rows show empty space (no locked blocks visible)
                 (EMPTY, Style::default())
             } else {
-                match game.board.get(row as i32, col as i32) {
-                    Some(Cell::Filled(color)) => (block_char, Style::default().fg(color)),
-                    _ => (EMPTY, Style::default()),
+                match game.board.get(row as i32, col as i32).and_then(cell_color) {
+                    Some(color) => (block_char, Style::default().fg(color)),
+                    None => (EMPTY, Style::default()),
                 }
             };
 
@@ -472,7 +701,7 @@ fn render_stats(frame: &mut Frame, area: Rect, game: &Game) {
                 Style::default().fg(Color::Cyan),
             )));
         }
-        GameMode::Sprint => {
+        GameMode::Sprint(_) | GameMode::Daily => {
             lines.push(Line::from(Span::styled("TIME", Style::default().fg(Color::Gray))));
             lines.push(Line::from(Span::styled(
                 game.mode_state.format_time(),
@@ -500,6 +729,140 @@ fn render_stats(frame: &mut Frame, area: Rect, game: &Game) {
                 Style::default().fg(Color::Yellow).bold(),
             )));
         }
+        GameMode::Blitz => {
+            lines.push(Line::from(Span::styled("TIME LEFT", Style::default().fg(Color::Gray))));
+            let remaining = game.mode_state.format_remaining().unwrap_or_default();
+            lines.push(Line::from(Span::styled(
+                remaining,
+                Style::default().fg(Color::Red).bold(),
+            )));
+            lines.push(Line::raw(""));
+            lines.push(Line::from(Span::styled("SCORE", Style::default().fg(Color::Gray))));
+            lines.push(Line::from(Span::styled(
+                format!("{}", game.score.points),
+                Style::default().fg(Color::Yellow).bold(),
+            )));
+            lines.push(Line::raw(""));
+            lines.push(Line::from(Span::styled("LEVEL", Style::default().fg(Color::Gray))));
+            lines.push(Line::from(Span::styled(
+                format!("{}", game.score.level),
+                Style::default().fg(Color::Cyan),
+            )));
+        }
+        GameMode::Master => {
+            if let Some(master) = &game.mode_state.master {
+                lines.push(Line::from(Span::styled("GRADE", Style::default().fg(Color::Gray))));
+                lines.push(Line::from(Span::styled(
+                    master.grade(),
+                    Style::default().fg(Color::Yellow).bold(),
+                )));
+                lines.push(Line::raw(""));
+                lines.push(Line::from(Span::styled("LEVEL", Style::default().fg(Color::Gray))));
+                lines.push(Line::from(Span::styled(
+                    format!("{:>3}/{}", master.level, master.section_end()),
+                    Style::default().fg(Color::Cyan),
+                )));
+                lines.push(Line::raw(""));
+                match master.roll_remaining(game.mode_state.elapsed) {
+                    Some(remaining) => {
+                        lines.push(Line::from(Span::styled("ROLL", Style::default().fg(Color::Gray))));
+                        lines.push(Line::from(Span::styled(
+                            format!("{:.1}", remaining.as_secs_f64()),
+                            Style::default().fg(Color::Magenta).bold(),
+                        )));
+                    }
+                    None => {
+                        lines.push(Line::from(Span::styled("TIME", Style::default().fg(Color::Gray))));
+                        lines.push(Line::from(Span::styled(
+                            game.mode_state.format_time(),
+                            Style::default().fg(Color::White),
+                        )));
+                    }
+                }
+            }
+        }
+        GameMode::Cheese(_) => {
+            lines.push(Line::from(Span::styled("TIME", Style::default().fg(Color::Gray))));
+            lines.push(Line::from(Span::styled(
+                game.mode_state.format_time(),
+                Style::default().fg(Color::Yellow).bold(),
+            )));
+            lines.push(Line::raw(""));
+            lines.push(Line::from(Span::styled("GARBAGE LEFT", Style::default().fg(Color::Gray))));
+            let remaining = game.mode_state.garbage_remaining().unwrap_or(0);
+            lines.push(Line::from(Span::styled(
+                format!("{}", remaining),
+                Style::default().fg(Color::Cyan),
+            )));
+        }
+        GameMode::Survival => {
+            lines.push(Line::from(Span::styled("TIME", Style::default().fg(Color::Gray))));
+            lines.push(Line::from(Span::styled(
+                game.mode_state.format_time(),
+                Style::default().fg(Color::Yellow).bold(),
+            )));
+            lines.push(Line::raw(""));
+            lines.push(Line::from(Span::styled("NEXT ROW", Style::default().fg(Color::Gray))));
+            let next_row = game
+                .mode_state
+                .survival
+                .as_ref()
+                .map_or(0.0, |survival| survival.time_to_rise(game.mode_state.elapsed).as_secs_f64());
+            lines.push(Line::from(Span::styled(
+                format!("{:.1}", next_row),
+                Style::default().fg(Color::Red).bold(),
+            )));
+        }
+        GameMode::Zen => {
+            lines.push(Line::from(Span::styled("SCORE", Style::default().fg(Color::Gray))));
+            lines.push(Line::from(Span::styled(
+                format!("{}", game.score.points),
+                Style::default().fg(Color::Yellow).bold(),
+            )));
+            lines.push(Line::raw(""));
+            lines.push(Line::from(Span::styled("PIECES", Style::default().fg(Color::Gray))));
+            lines.push(Line::from(Span::styled(
+                format!("{}", game.pieces_placed),
+                Style::default().fg(Color::Cyan),
+            )));
+        }
+        GameMode::Practice => {
+            lines.push(Line::from(Span::styled("PIECES", Style::default().fg(Color::Gray))));
+            lines.push(Line::from(Span::styled(
+                format!("{}", game.pieces_placed),
+                Style::default().fg(Color::Yellow).bold(),
+            )));
+            lines.push(Line::raw(""));
+            lines.push(Line::from(Span::styled("UNDO", Style::default().fg(Color::Gray))));
+            let undo_count = game.mode_state.practice.as_ref().map_or(0, |practice| practice.undo_count());
+            lines.push(Line::from(Span::styled(
+                format!("{}", undo_count),
+                Style::default().fg(Color::Cyan),
+            )));
+        }
+        GameMode::Custom => {
+            match game.mode_state.format_remaining() {
+                Some(remaining) => {
+                    lines.push(Line::from(Span::styled("TIME LEFT", Style::default().fg(Color::Gray))));
+                    lines.push(Line::from(Span::styled(remaining, Style::default().fg(Color::Red).bold())));
+                }
+                None => {
+                    lines.push(Line::from(Span::styled("TIME", Style::default().fg(Color::Gray))));
+                    lines.push(Line::from(Span::styled(
+                        game.mode_state.format_time(),
+                        Style::default().fg(Color::Yellow).bold(),
+                    )));
+                }
+            }
+            lines.push(Line::raw(""));
+            let (label, value) = match game.mode_state.custom_goal() {
+                Some(Goal::Lines(_)) => ("LINES LEFT", game.mode_state.lines_remaining(game.score.lines).unwrap_or(0) as u64),
+                Some(Goal::Pieces(target)) => ("PIECES LEFT", target.saturating_sub(game.pieces_placed) as u64),
+                _ => ("SCORE", game.score.points),
+            };
+            lines.push(Line::from(Span::styled(label, Style::default().fg(Color::Gray))));
+            lines.push(Line::from(Span::styled(format!("{}", value), Style::default().fg(Color::Cyan))));
+        }
         GameMode::Versus => {
             lines.push(Line::from(Span::styled("TIME", Style::default().fg(Color::Gray))));
             lines.push(Line::from(Span::styled(
@@ -509,7 +872,7 @@ fn render_stats(frame: &mut Frame, area: Rect, game: &Game) {
             lines.push(Line::raw(""));
             lines.push(Line::from(Span::styled("ATTACK", Style::default().fg(Color::Gray))));
             lines.push(Line::from(Span::styled(
-                format!("{}", game.score.points), // Will track garbage sent
+                format!("{}", game.garbage_sent),
                 Style::default().fg(Color::Red).bold(),
             )));
         }
@@ -564,8 +927,27 @@ fn render_countdown(frame: &mut Frame, area: Rect, count: u8) {
 
 /// Render an overlay (for pause/game over)
 fn render_overlay(frame: &mut Frame, area: Rect, title: &str, subtitle: &str) {
+    render_popup(frame, area, title, vec![Line::styled(subtitle, Style::default().fg(Color::Gray))]);
+}
+
+/// Render the end-of-game overlay with the rules the game was played with
+fn render_results(frame: &mut Frame, area: Rect, title: &str, subtitle: &str, rules: &Rules) {
+    let rules_line = format!("{} · {}", rules.rotation_system.name(), rules.randomizer.name());
+    render_popup(
+        frame,
+        area,
+        title,
+        vec![
+            Line::styled(subtitle, Style::default().fg(Color::Gray)),
+            Line::styled(rules_line, Style::default().fg(Color::DarkGray)),
+        ],
+    );
+}
+
+/// Render a centered popup with a title and lines of text below it
+fn render_popup(frame: &mut Frame, area: Rect, title: &str, lines: Vec<Line>) {
     let popup_width = 24u16;
-    let popup_height = 5u16;
+    let popup_height = 4 + lines.len() as u16;
     let popup_area = center_rect(area, popup_width, popup_height);
 
     // Clear the background
@@ -579,11 +961,11 @@ fn render_overlay(frame: &mut Frame, area: Rect, title: &str, subtitle: &str) {
     let inner = block.inner(popup_area);
     frame.render_widget(block, popup_area);
 
-    let text = vec![
+    let mut text = vec![
         Line::styled(title, Style::default().fg(Color::Yellow).bold()),
         Line::raw(""),
-        Line::styled(subtitle, Style::default().fg(Color::Gray)),
     ];
+    text.extend(lines);
 
     let paragraph = Paragraph::new(text).alignment(Alignment::Center);
     frame.render_widget(paragraph, inner);
@@ -634,7 +1016,7 @@ pub fn render_versus(
             render_waiting_screen(frame, area, "Disconnected", None);
         }
         _ => {
-            render_game(frame, game, settings);
+            render_game(frame, game, None, settings);
         }
     }
 }
@@ -758,15 +1140,16 @@ fn render_versus_game(
     let (block_char, _) = settings.visual.block_chars();
 
     // Wide layout: our board | middle info | opponent mini board
-    let versus_width = 72u16;
-    let versus_height = 24u16;
-    let versus_area = center_rect(area, versus_width, versus_height);
+    let (game_width, versus_height) = game_size(&game.board);
+    let our_width = game_width + 1;
+    let versus_area = center_rect(area, our_width + 22, versus_height);
+    let (board_width, _) = board_box_size(&game.board);
 
     let main_layout = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
-            Constraint::Length(50), // Our full game
-            Constraint::Length(22), // Opponent mini board + stats
+            Constraint::Length(our_width), // Our full game
+            Constraint::Length(22),        // Opponent mini board + stats
         ])
         .split(versus_area);
 
@@ -774,14 +1157,16 @@ fn render_versus_game(
     let our_layout = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
-            Constraint::Length(12), // Hold
-            Constraint::Length(22), // Board
-            Constraint::Length(16), // Next + stats
+            Constraint::Length(HOLD_WIDTH),  // Hold
+            Constraint::Length(1),           // Incoming garbage meter
+            Constraint::Length(board_width), // Board
+            Constraint::Length(PANEL_WIDTH), // Next + stats
         ])
         .split(main_layout[0]);
 
-    render_hold(frame, our_layout[0], game.hold_piece, block_char);
-    render_board(frame, our_layout[1], game, settings);
+    render_hold(frame, our_layout[0], game.hold_piece, game.rules().rotation_system, block_char);
+    render_garbage_meter(frame, our_layout[1], game);
+    render_board(frame, our_layout[2], game, settings);
 
     let our_right = Layout::default()
         .direction(Direction::Vertical)
@@ -789,9 +1174,9 @@ fn render_versus_game(
             Constraint::Length(14), // Next
             Constraint::Min(6),     // Stats
         ])
-        .split(our_layout[2]);
+        .split(our_layout[3]);
 
-    render_next_queue(frame, our_right[0], game.preview(), block_char);
+    render_next_queue(frame, our_right[0], game.preview(), game.rules().rotation_system, block_char);
     render_stats(frame, our_right[1], game);
 
     // Render opponent on the right
@@ -807,6 +1192,42 @@ fn render_versus_game(
     render_opponent_stats(frame, opp_layout[1], session);
 }
 
+/// Render the incoming garbage meter (one cell per queued line, bottom up)
+/// Red lines enter on the next lock, yellow ones soon, gray ones later
+fn render_garbage_meter(frame: &mut Frame, area: Rect, game: &Game) {
+    let queue = &game.garbage_queue;
+    let delay = queue.delay();
+
+    // Color of each queued line, oldest attack at the bottom
+    let mut meter: Vec<Color> = Vec::new();
+    for entry in queue.entries() {
+        let remaining = queue.time_until_ready(entry, game.clock());
+        let color = if remaining.is_zero() {
+            Color::Red
+        } else if remaining <= delay / 2 {
+            Color::Yellow
+        } else {
+            Color::Gray
+        };
+        meter.extend(std::iter::repeat_n(color, entry.lines as usize));
+    }
+
+    // Align rows with the board: top border, buffer rows, then the board,
+    // skipping the top rows the board hides when it doesn't fit
+    let total_visible_rows = game.board.height() + VISIBLE_BUFFER;
+    let hidden_rows = total_visible_rows.saturating_sub(area.height.saturating_sub(2) as usize);
+    let mut lines: Vec<Line> = vec![Line::raw("")];
+    for row in (0..total_visible_rows).rev().skip(hidden_rows) {
+        match meter.get(row) {
+            Some(&color) => lines.push(Line::styled("█", Style::default().fg(color))),
+            None => lines.push(Line::raw(" ")),
+        }
+    }
+
+    let paragraph = Paragraph::new(lines);
+    frame.render_widget(paragraph, area);
+}
+
 /// Render a small opponent board preview
 fn render_opponent_board(
     frame: &mut Frame,
@@ -824,6 +1245,12 @@ fn render_opponent_board(
             Style::default().fg(Color::Gray)
         });
 
+    render_mini_board(frame, area, block, &session.opponent.board, Style::default(), block_char);
+}
+
+/// Render a board (visible rows, bottom first) scaled down to every other
+/// row, with `style` under each block's color
+fn render_mini_board(frame: &mut Frame, area: Rect, block: Block, board: &[Vec<Cell>], style: Style, block_char: &str) {
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
@@ -832,19 +1259,17 @@ fn render_opponent_board(
     let visible_rows = inner.height as usize;
 
     for screen_row in 0..visible_rows {
-        let board_row = (crate::board::BOARD_HEIGHT - 1) - (screen_row * 2);
-        if board_row >= crate::board::BOARD_HEIGHT {
+        let Some(board_row) = board.len().checked_sub(1 + screen_row * 2) else {
             continue;
-        }
+        };
 
         let mut spans = Vec::new();
-        for col in 0..crate::board::BOARD_WIDTH {
-            let cell = session.opponent.board[board_row][col];
-            match cell {
-                Cell::Empty => spans.push(Span::raw(" ")),
-                Cell::Filled(color) => spans.push(Span::styled(
+        for &cell in &board[board_row] {
+            match cell_color(cell) {
+                None => spans.push(Span::raw(" ")),
+                Some(color) => spans.push(Span::styled(
                     &block_char[0..block_char.chars().next().map(|c| c.len_utf8()).unwrap_or(1)],
-                    Style::default().fg(color),
+                    style.fg(color),
                 )),
             }
         }
@@ -855,12 +1280,38 @@ fn render_opponent_board(
     frame.render_widget(paragraph, inner);
 }
 
+/// Render the ghost of the personal best run, faint beside our board
+fn render_ghost(frame: &mut Frame, area: Rect, ghost: &Game, block_char: &str) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(12), // Mini board
+            Constraint::Min(6),     // Stats
+        ])
+        .split(area);
+
+    let block = Block::default()
+        .title(" PB ")
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray));
+    let board: Vec<Vec<Cell>> = ghost.board.visible_rows().map(|(_, row)| row.to_vec()).collect();
+    render_mini_board(frame, layout[0], block, &board, Style::default().dim(), block_char);
+
+    render_mini_stats(frame, layout[1], ghost.score.points, ghost.score.lines);
+}
+
 /// Render opponent stats
 fn render_opponent_stats(
     frame: &mut Frame,
     area: Rect,
     session: &crate::multiplayer::MultiplayerSession,
 ) {
+    render_mini_stats(frame, area, session.opponent.score, session.opponent.lines);
+}
+
+/// Render score and lines beside a mini board
+fn render_mini_stats(frame: &mut Frame, area: Rect, score: u64, lines_cleared: u32) {
     let block = Block::default()
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Gray));
@@ -871,13 +1322,13 @@ fn render_opponent_stats(
     let lines = vec![
         Line::from(Span::styled("SCORE", Style::default().fg(Color::Gray))),
         Line::from(Span::styled(
-            format!("{}", session.opponent.score),
+            format!("{}", score),
             Style::default().fg(Color::White).bold(),
         )),
         Line::raw(""),
         Line::from(Span::styled("LINES", Style::default().fg(Color::Gray))),
         Line::from(Span::styled(
-            format!("{}", session.opponent.lines),
+            format!("{}", lines_cleared),
             Style::default().fg(Color::Green),
         )),
     ];